@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// A Missing or Bad column of a dataset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub dataset: String,
+    pub column: String,
+    /// `Missing` or `Bad`
+    pub suggestion: String,
+    pub comments: String,
+}
+
+impl Finding {
+    fn is_bad(&self) -> bool {
+        self.suggestion == "Bad"
+    }
+}
+
+/// The findings recorded with `--write-baseline`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub findings: Vec<Finding>,
+}
+
+/// The findings compared to a baseline
+#[derive(Debug, Default)]
+pub struct BaselineComparison<'a> {
+    /// Columns that were not findings in the baseline, or went from Missing
+    /// to Bad
+    pub new: Vec<&'a Finding>,
+    /// Baseline findings that are no longer reported
+    pub fixed: Vec<&'a Finding>,
+}
+
+impl Baseline {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to read baseline {}", path))
+    }
+
+    pub fn write(&self, path: &str) -> anyhow::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Compare the current findings with the baseline. A Bad column that is
+    /// now Missing is neither new nor fixed.
+    pub fn compare<'a>(&'a self, findings: &'a [Finding]) -> BaselineComparison<'a> {
+        let index = |findings: &'a [Finding]| {
+            findings
+                .iter()
+                .map(|f| ((f.dataset.as_str(), f.column.as_str()), f))
+                .collect::<HashMap<_, _>>()
+        };
+        let baseline = index(self.findings.as_slice());
+        let current = index(findings);
+        let new = findings
+            .iter()
+            .filter(|f| {
+                baseline
+                    .get(&(f.dataset.as_str(), f.column.as_str()))
+                    .is_none_or(|b| f.is_bad() && !b.is_bad())
+            })
+            .collect();
+        let fixed = self
+            .findings
+            .iter()
+            .filter(|b| !current.contains_key(&(b.dataset.as_str(), b.column.as_str())))
+            .collect();
+        BaselineComparison { new, fixed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(dataset: &str, column: &str, suggestion: &str) -> Finding {
+        Finding {
+            dataset: dataset.to_owned(),
+            column: column.to_owned(),
+            suggestion: suggestion.to_owned(),
+            comments: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Baseline {
+            findings: vec![
+                finding("shop", "TaskId", "Bad"),
+                finding("shop", "app.cart.total", "Missing"),
+                finding("shop", "app.order.id", "Missing"),
+                finding("billing", "http.method", "Bad"),
+            ],
+        };
+        let findings = vec![
+            finding("shop", "TaskId", "Missing"),
+            finding("shop", "app.cart.total", "Bad"),
+            finding("shop", "app.user.id", "Missing"),
+            finding("billing", "TaskId", "Bad"),
+        ];
+        let comparison = baseline.compare(&findings);
+        let columns = |findings: &[&Finding]| {
+            findings
+                .iter()
+                .map(|f| format!("{}/{}", f.dataset, f.column))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            columns(&comparison.new),
+            vec!["shop/app.cart.total", "shop/app.user.id", "billing/TaskId"]
+        );
+        assert_eq!(
+            columns(&comparison.fixed),
+            vec!["shop/app.order.id", "billing/http.method"]
+        );
+    }
+}
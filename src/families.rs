@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+/// Column names that differ only by dynamically generated parts, e.g.
+/// `cache.hit.user_123` and `cache.hit.user_456`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnFamily {
+    /// The name with each dynamic part replaced, e.g. `cache.hit.user_{n}`
+    pub pattern: String,
+    pub columns: Vec<String>,
+    /// The segments before the first dynamic one, e.g. `cache.hit`
+    pub prefix: String,
+    /// Whether the dynamic parts are all in the last segment, so a template
+    /// attribute with the prefix can hold the values
+    pub trailing: bool,
+    /// The name without the dynamic segments, e.g. `job.step.duration` for
+    /// `job.step.{n}.duration`
+    pub static_name: String,
+}
+
+/// The placeholder for a dynamically generated token, if it is one
+fn dynamic_token(token: &str) -> Option<&'static str> {
+    if token.is_empty() {
+        return None;
+    }
+    if token.chars().all(|c| c.is_ascii_digit()) {
+        return Some("{n}");
+    }
+    let has_digit = token.chars().any(|c| c.is_ascii_digit());
+    if token.len() >= 8 && has_digit && token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some("{hash}");
+    }
+    if token.len() >= 12
+        && has_digit
+        && token.chars().any(|c| c.is_ascii_alphabetic())
+        && token.chars().all(|c| c.is_ascii_alphanumeric())
+        && entropy(token) >= 3.0
+    {
+        return Some("{id}");
+    }
+    None
+}
+
+/// Shannon entropy in bits per character
+fn entropy(token: &str) -> f64 {
+    let mut counts = BTreeMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = token.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn is_uuid(segment: &str) -> bool {
+    let parts = segment.split('-').collect::<Vec<_>>();
+    parts.iter().map(|p| p.len()).eq([8, 4, 4, 4, 12])
+        && parts
+            .iter()
+            .all(|p| p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Replace the dynamic tokens of a segment, which are split on `_` and `-`.
+/// `None` if the segment has no dynamic parts.
+fn normalize_segment(segment: &str) -> Option<String> {
+    if is_uuid(segment) {
+        return Some("{uuid}".to_owned());
+    }
+    let mut normalized = String::new();
+    let mut dynamic = false;
+    for piece in segment.split_inclusive(['_', '-']) {
+        let token = piece.strip_suffix(['_', '-']).unwrap_or(piece);
+        match dynamic_token(token) {
+            Some(placeholder) => {
+                normalized.push_str(placeholder);
+                normalized.push_str(&piece[token.len()..]);
+                dynamic = true;
+            }
+            None => normalized.push_str(piece),
+        }
+    }
+    dynamic.then_some(normalized)
+}
+
+/// Group the names that share a pattern of dynamic parts. Only families with
+/// at least `min_size` columns are returned, largest first.
+pub fn find_families<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    min_size: usize,
+) -> Vec<ColumnFamily> {
+    let mut families: BTreeMap<String, ColumnFamily> = BTreeMap::new();
+    for name in names {
+        let segments = name.split('.').collect::<Vec<_>>();
+        let normalized = segments
+            .iter()
+            .map(|s| normalize_segment(s))
+            .collect::<Vec<_>>();
+        let Some(first_dynamic) = normalized.iter().position(|n| n.is_some()) else {
+            continue;
+        };
+        let pattern = segments
+            .iter()
+            .zip(&normalized)
+            .map(|(s, n)| n.as_deref().unwrap_or(*s))
+            .collect::<Vec<_>>()
+            .join(".");
+        let family = families
+            .entry(pattern.clone())
+            .or_insert_with(|| ColumnFamily {
+                pattern,
+                columns: vec![],
+                prefix: segments[..first_dynamic].join("."),
+                trailing: first_dynamic == segments.len() - 1,
+                static_name: segments
+                    .iter()
+                    .zip(&normalized)
+                    .filter(|(_, n)| n.is_none())
+                    .map(|(s, _)| *s)
+                    .collect::<Vec<_>>()
+                    .join("."),
+            });
+        family.columns.push(name.to_owned());
+    }
+    let mut families = families
+        .into_values()
+        .filter(|f| f.columns.len() >= min_size)
+        .collect::<Vec<_>>();
+    for family in &mut families {
+        family.columns.sort();
+    }
+    families.sort_by(|a, b| {
+        b.columns
+            .len()
+            .cmp(&a.columns.len())
+            .then(a.pattern.cmp(&b.pattern))
+    });
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_segment() {
+        assert_eq!(normalize_segment("user_123"), Some("user_{n}".to_owned()));
+        assert_eq!(normalize_segment("17"), Some("{n}".to_owned()));
+        assert_eq!(
+            normalize_segment("x-req-4f2a9c1d77"),
+            Some("x-req-{hash}".to_owned())
+        );
+        assert_eq!(
+            normalize_segment("3f2b8c1e-9d4a-4b6f-8e2a-1c5d7f9b0a3e"),
+            Some("{uuid}".to_owned())
+        );
+        assert_eq!(
+            normalize_segment("sess_Qx7Lm2Pz9Kw4"),
+            Some("sess_{id}".to_owned())
+        );
+        assert_eq!(normalize_segment("status_code"), None);
+        assert_eq!(normalize_segment("sha256"), None);
+        assert_eq!(normalize_segment("deadbeef"), None);
+    }
+
+    #[test]
+    fn test_find_families() {
+        let names = [
+            "cache.hit.user_123",
+            "cache.hit.user_456",
+            "cache.hit.user_789",
+            "job.step.1.duration",
+            "job.step.2.duration",
+            "job.step.3",
+            "http.route",
+        ];
+        let families = find_families(names, 2);
+        assert_eq!(families.len(), 2);
+        assert_eq!(families[0].pattern, "cache.hit.user_{n}");
+        assert_eq!(families[0].columns.len(), 3);
+        assert_eq!(families[0].prefix, "cache.hit");
+        assert!(families[0].trailing);
+        assert_eq!(families[1].pattern, "job.step.{n}.duration");
+        assert_eq!(families[1].prefix, "job.step");
+        assert!(!families[1].trailing);
+        assert_eq!(families[1].static_name, "job.step.duration");
+    }
+}
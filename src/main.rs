@@ -1,19 +1,47 @@
+mod baseline;
+mod families;
+mod namespaces;
 mod octo;
+mod otlp;
+mod pii;
+mod query;
+mod rules;
 mod semconv;
+mod snapshot;
+mod source;
+mod suppressions;
+mod synonyms;
+mod values;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{Display, Formatter},
     fs::File,
     io::{BufWriter, Write},
     path::Path,
 };
 
 use anyhow::{Context, Ok};
-use clap::Parser;
+use baseline::{Baseline, BaselineComparison, Finding};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Parser, Subcommand, ValueEnum,
+};
 use colored::Colorize;
-use honeycomb_client::honeycomb::Column;
-use indicatif::ProgressBar;
-use semconv::{SemanticConventions, Suggestion};
+use families::ColumnFamily;
+use namespaces::{NamespaceNode, NamespaceTree};
+use otlp::OtlpSource;
+use pii::PiiKind;
+use rules::OrgRules;
+use semconv::{
+    ComplexType, Member, NamespaceLimits, NamingConfig, NamingRule, SemanticConventions,
+    Suggestion, SuggestionComment,
+};
+use serde::Serialize;
+use snapshot::{Column, Dataset, Snapshot};
+use source::{add_scopes, ColumnSource, HoneycombSource, SnapshotSource};
+use suppressions::{Suppression, Suppressions};
+use values::{ValueRules, ValueViolation};
 
 // For each dataset get all the columns and put them in a map of column_name -> ColumnUsage
 // ColumnUsage contains a column and a mapping of datasets where this column is used
@@ -21,38 +49,74 @@ use semconv::{SemanticConventions, Suggestion};
 struct ColumnUsage {
     column: Column,
     datasets: Vec<bool>,
+    // The column type in each dataset using it
+    types: Vec<Option<String>>,
+    // The suggestion in each dataset using it, which depends on the column's
+    // type and scopes there and the dataset's organisation rules
+    suggestions: Vec<Option<Suggestion>>,
+    // The suggestions of all the datasets merged, for the column reports
     suggestion: Suggestion,
+    // The stability of the matching convention if it is not stable
+    unstable: Option<String>,
+    // Whether the column is suppressed in each dataset
+    suppressed: Vec<bool>,
 }
 
 impl ColumnUsage {
-    fn new(
-        column: Column,
-        suggestion: Suggestion,
-        dataset_len: usize,
-        initial_true: usize,
-    ) -> Self {
-        let mut datasets = vec![false; dataset_len];
-        datasets[initial_true] = true;
+    fn new(column: Column, unstable: Option<String>, dataset_len: usize) -> Self {
         Self {
             column,
-            datasets,
-            suggestion,
+            datasets: vec![false; dataset_len],
+            types: vec![None; dataset_len],
+            suggestions: vec![None; dataset_len],
+            suggestion: Suggestion::Matching,
+            unstable,
+            suppressed: vec![false; dataset_len],
         }
     }
 
-    fn datasets_as_string(&self) -> String {
-        let mut bools = vec![];
-        let mut total = 0usize;
-        for d in &self.datasets {
-            if *d {
-                bools.push("x,");
-                total += 1;
-            } else {
-                bools.push(",");
+    fn add_dataset(&mut self, dataset_num: usize, column_type: String, suggestion: Suggestion) {
+        self.datasets[dataset_num] = true;
+        self.types[dataset_num] = Some(column_type);
+        self.suggestions[dataset_num] = Some(suggestion);
+        self.suggestion = Suggestion::merge(self.suggestions.iter().flatten());
+    }
+
+    /// Each type the column has to the datasets holding it, when the
+    /// datasets do not agree on the type
+    fn type_conflicts(&self) -> Option<BTreeMap<&str, Vec<usize>>> {
+        let mut types: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (dataset_num, t) in self.types.iter().enumerate() {
+            if let Some(t) = t {
+                types.entry(t.as_str()).or_default().push(dataset_num);
             }
         }
-        format!("{},{}", total, bools.join(""))
+        (types.len() > 1).then_some(types)
+    }
+}
+
+/// The number of datasets using a column followed by an `x` for each one
+fn datasets_as_string(datasets: &[bool]) -> String {
+    let mut bools = vec![];
+    let mut total = 0usize;
+    for d in datasets {
+        if *d {
+            bools.push("x,");
+            total += 1;
+        } else {
+            bools.push(",");
+        }
     }
+    format!("{},{}", total, bools.join(""))
+}
+
+/// How to treat columns matching conventions that are not yet stable
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum UnstableMode {
+    /// Count as matching and report them as a warning
+    Warning,
+    /// Count as bad
+    Bad,
 }
 
 #[derive(Debug)]
@@ -60,6 +124,11 @@ struct DatasetHealth {
     matching: usize,
     missing: usize,
     bad: usize,
+    // Columns matching an experimental or development convention. These are
+    // also counted as matching or bad depending on the UnstableMode.
+    unstable: usize,
+    // Missing or bad columns that are suppressed, these are not scored
+    suppressed: usize,
 }
 
 impl DatasetHealth {
@@ -68,6 +137,8 @@ impl DatasetHealth {
             matching: 0,
             missing: 0,
             bad: 0,
+            unstable: 0,
+            suppressed: 0,
         }
     }
 
@@ -81,108 +152,587 @@ impl DatasetHealth {
     }
 }
 
+/// How often spans have an attribute of the convention they follow
+#[derive(Debug)]
+struct RequirementRow {
+    attribute: String,
+    level: String,
+    percent: f64,
+}
+
+#[derive(Debug)]
+struct RequirementReport {
+    dataset: String,
+    group: String,
+    span_kind: String,
+    spans: u64,
+    rows: Vec<RequirementRow>,
+}
+
+/// Spellings of the same column name across the datasets, e.g. `user_id`,
+/// `userId` and `user.id`, and which datasets use each
+#[derive(Debug)]
+struct SynonymGroup {
+    canonical: String,
+    // Whether the canonical name comes from the model rather than usage
+    from_model: bool,
+    spellings: Vec<(String, Vec<bool>)>,
+}
+
+/// A model attribute or template and the datasets using it
+#[derive(Debug)]
+struct CoverageRow {
+    attribute: String,
+    template: bool,
+    datasets: Vec<bool>,
+}
+
+/// The fewest columns sharing a pattern of dynamic parts to report as a
+/// family
+const MIN_FAMILY_SIZE: usize = 3;
+
+/// Attributes that should only have a few values, as well as enums. Span
+/// names and routes holding raw URLs or IDs break queries.
+const LOW_CARDINALITY: &[&str] = &[
+    "name",
+    "span.kind",
+    "http.route",
+    "url.template",
+    "db.operation.name",
+    "db.collection.name",
+    "rpc.service",
+    "rpc.method",
+    "messaging.operation.name",
+];
+
+/// The number of most used values shown for a high cardinality column
+const TOP_VALUES: usize = 5;
+
+/// A column with more distinct values than the threshold, and its most used
+/// values
+#[derive(Debug)]
+struct CardinalityRow {
+    column: String,
+    distinct: u64,
+    top: Vec<(String, u64)>,
+}
+
+/// The high cardinality columns of the low cardinality columns checked in a
+/// dataset
+#[derive(Debug)]
+struct CardinalityReport {
+    dataset: String,
+    checked: usize,
+    rows: Vec<CardinalityRow>,
+}
+
+/// A sampled value that violates its attribute's type or constraints
+#[derive(Debug)]
+struct ValueFinding {
+    value: String,
+    count: u64,
+    violation: ValueViolation,
+}
+
+impl Display for ValueFinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" ({}) {}", self.value, self.count, self.violation)
+    }
+}
+
+/// The value violations of the checked columns in a dataset
+#[derive(Debug)]
+struct ValueReport {
+    dataset: String,
+    checked: usize,
+    columns: Vec<(String, Vec<ValueFinding>)>,
+}
+
+/// Values of a column that look like personal data or secrets, with a
+/// masked sample of the most used one
+#[derive(Debug)]
+struct PiiFinding {
+    column: String,
+    kind: PiiKind,
+    count: u64,
+    sample: String,
+}
+
+/// The events of a column with values of one PII kind, and the most used
+/// of those values
+#[derive(Debug, Default)]
+struct PiiSample {
+    count: u64,
+    value: String,
+    value_count: u64,
+}
+
+/// The PII findings of the string columns scanned in a dataset
+#[derive(Debug)]
+struct PiiReport {
+    dataset: String,
+    scanned: usize,
+    // String columns over `--max-pii-columns` that were not scanned
+    skipped: usize,
+    findings: Vec<PiiFinding>,
+}
+
+/// How serious an undefined variant is. Open enums allow custom values so
+/// their variants are only informational, unless they look like a typo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum EnumFindingKind {
+    Info,
+    Warning,
+    Error,
+}
+
+impl EnumFindingKind {
+    fn colorize(&self, s: &str) -> colored::ColoredString {
+        match self {
+            EnumFindingKind::Info => s.normal(),
+            EnumFindingKind::Warning => s.yellow(),
+            EnumFindingKind::Error => s.red(),
+        }
+    }
+}
+
+impl Display for EnumFindingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnumFindingKind::Info => write!(f, "Info"),
+            EnumFindingKind::Warning => write!(f, "Warning"),
+            EnumFindingKind::Error => write!(f, "Error"),
+        }
+    }
+}
+
+/// The defined member an undefined variant was most likely meant to be
+#[derive(Debug, Clone, Serialize)]
+struct VariantSuggestion {
+    id: String,
+    value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    brief: Option<String>,
+}
+
+impl From<&Member> for VariantSuggestion {
+    fn from(member: &Member) -> Self {
+        VariantSuggestion {
+            id: member.id.clone(),
+            value: member.value.to_string(),
+            brief: member.brief.clone(),
+        }
+    }
+}
+
+/// A variant found in a dataset that is not defined by the enum, with the
+/// closest defined variant if it looks like a typo
+#[derive(Debug, Clone, Serialize)]
+struct UndefinedVariant {
+    value: String,
+    count: u64,
+    kind: EnumFindingKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<VariantSuggestion>,
+}
+
+impl UndefinedVariant {
+    fn new(enum_type: &ComplexType, value: String, count: u64) -> Self {
+        let suggestion = enum_type
+            .closest_variant(&value)
+            .map(VariantSuggestion::from);
+        let kind = match (enum_type.allow_custom_values, &suggestion) {
+            (false, _) => EnumFindingKind::Error,
+            (true, Some(_)) => EnumFindingKind::Warning,
+            (true, None) => EnumFindingKind::Info,
+        };
+        UndefinedVariant {
+            value,
+            count,
+            kind,
+            suggestion,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        match &self.suggestion {
+            Some(s) => format!("`{}` ({}) → `{}`", self.value, self.count, s.value),
+            None => format!("`{}` ({})", self.value, self.count),
+        }
+    }
+}
+
+impl Display for UndefinedVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Quote the value when the whitespace would otherwise be invisible
+        if self.value.trim() != self.value {
+            write!(f, "\"{}\" ({})", self.value, self.count)?;
+        } else {
+            write!(f, "{} ({})", self.value, self.count)?;
+        }
+        if let Some(s) = &self.suggestion {
+            write!(f, " → {}", s.value)?;
+        }
+        std::fmt::Result::Ok(())
+    }
+}
+
+fn join_variants(variants: &[UndefinedVariant], separator: &str) -> String {
+    variants
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// List the suppressions that still apply with their reasons and the number
+/// of columns they suppressed
+fn print_suppressions(active: &[(&Suppression, usize)]) {
+    if active.is_empty() {
+        return;
+    }
+    println!("\n{}", "Suppressions".bold());
+    let longest = "Column".len().max(
+        active
+            .iter()
+            .map(|(s, _)| s.column.len())
+            .max()
+            .unwrap_or(0),
+    );
+    println!(
+        "{:>width$} {:>7} {:10} {}",
+        "Column".bold(),
+        "Columns".bold(),
+        "Expires".bold(),
+        "Reason".bold(),
+        width = longest
+    );
+    for (suppression, count) in active {
+        let datasets = suppression
+            .datasets
+            .as_ref()
+            .map(|d| format!(" ({})", d.join(", ")))
+            .unwrap_or_default();
+        println!(
+            "{:>width$} {:>7} {:10} {}{}",
+            suppression.column,
+            count,
+            suppression
+                .expires
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            suppression.reason,
+            datasets,
+            width = longest
+        );
+    }
+}
+
+/// List the suppressions past their expiry date so they are renewed or
+/// removed. Their columns are scored again.
+fn print_expired_suppressions(expired: &[&Suppression]) {
+    if expired.is_empty() {
+        return;
+    }
+    println!("\n{}", "Expired suppressions".bold().red());
+    let longest = "Column"
+        .len()
+        .max(expired.iter().map(|s| s.column.len()).max().unwrap_or(0));
+    println!(
+        "{:>width$} {:10} {}",
+        "Column".bold(),
+        "Expired".bold(),
+        "Reason".bold(),
+        width = longest
+    );
+    for suppression in expired {
+        let datasets = suppression
+            .datasets
+            .as_ref()
+            .map(|d| format!(" ({})", d.join(", ")))
+            .unwrap_or_default();
+        println!(
+            "{:>width$} {:10} {}{}",
+            suppression.column,
+            suppression
+                .expires
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            suppression.reason,
+            datasets,
+            width = longest
+        );
+    }
+}
+
+/// How serious a suggestion is, to find the worst of several
+fn suggestion_rank(suggestion: &Suggestion) -> u8 {
+    match suggestion {
+        Suggestion::Matching => 0,
+        Suggestion::Missing(_) => 1,
+        Suggestion::Bad(_) => 2,
+    }
+}
+
+/// The most serious finding among the undefined variants
+fn worst_kind(variants: &[UndefinedVariant]) -> Option<EnumFindingKind> {
+    variants.iter().map(|v| v.kind).max()
+}
+
+/// The undefined variants of an enum column in each dataset, `None` where
+/// the dataset does not have the column
+#[derive(Debug)]
+struct EnumUsage {
+    column: String,
+    undefined: Vec<Option<Vec<UndefinedVariant>>>,
+}
+
+/// The proportion of a dataset's enum columns with no undefined variants,
+/// other than the custom values of open enums
+fn enum_score(enum_report_rows: &[EnumUsage], dataset_num: usize) -> Option<f64> {
+    let used = enum_report_rows
+        .iter()
+        .filter_map(|r| r.undefined[dataset_num].as_ref())
+        .collect::<Vec<_>>();
+    if used.is_empty() {
+        return None;
+    }
+    let clean = used
+        .iter()
+        .filter(|u| worst_kind(u).is_none_or(|k| k == EnumFindingKind::Info))
+        .count();
+    Some((clean as f64 / used.len() as f64) * 100.0)
+}
+
 #[derive(Debug)]
 struct ColumnUsageMap {
     map: HashMap<String, ColumnUsage>,
     datasets: Vec<String>,
     dataset_health: Vec<DatasetHealth>,
+    // Families of dynamically named columns, reported and scored once each
+    families: Vec<ColumnFamily>,
+    // The column names in a family to the index of the family
+    family_members: HashMap<String, usize>,
     semconv: SemanticConventions,
-    max_last_written_days: usize,
+    source: Box<dyn ColumnSource>,
 }
 
 impl ColumnUsageMap {
-    async fn new(
-        root_dirs: &[String],
-        include_datasets: Option<HashSet<String>>,
-        max_last_written_days: usize,
-    ) -> anyhow::Result<Self> {
-        let sc = SemanticConventions::new(root_dirs)?;
-
+    fn new(
+        sc: SemanticConventions,
+        source: Box<dyn ColumnSource>,
+        datasets: Vec<Dataset>,
+        unstable_mode: UnstableMode,
+        org_rules: &OrgRules,
+        suppressions: &Suppressions,
+    ) -> Self {
         let mut cm = ColumnUsageMap {
             map: HashMap::new(),
-            datasets: vec![],
+            datasets: datasets.iter().map(|d| d.slug.clone()).collect(),
             dataset_health: vec![],
+            families: vec![],
+            family_members: HashMap::new(),
             semconv: sc,
-            max_last_written_days,
+            source,
         };
-        let hc = honeycomb_client::get_honeycomb(&["columns", "createDatasets"])
-            .await?
-            .context("API key does not have required access")?;
-
-        let dataset_slugs = hc
-            .get_dataset_slugs(max_last_written_days as i64, include_datasets)
-            .await?;
-
-        cm.datasets = dataset_slugs;
-        let bar = ProgressBar::new(cm.datasets.len() as u64)
-            .with_style(
-                indicatif::ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-                    .unwrap(),
-            )
-            .with_message("Reading datasets...");
-        bar.inc(0);
-        let mut dataset_num = 0;
-        hc.process_datasets_columns(max_last_written_days as i64, &cm.datasets, |_, columns| {
-            bar.inc(1);
-            let mut dataset_health = DatasetHealth::new();
-            for column in columns {
-                let health: Suggestion;
-                if let Some(cu) = cm.map.get_mut(&column.key_name) {
-                    cu.datasets[dataset_num] = true;
-                    health = cu.suggestion.clone();
-                } else {
-                    let key_name = column.key_name.clone();
-                    let suggestion = cm.semconv.get_suggestion(&key_name);
-                    let cu = ColumnUsage::new(
-                        column,
-                        suggestion.clone(),
-                        cm.datasets.len(),
-                        dataset_num,
-                    );
-                    cm.map.insert(key_name, cu);
-                    health = suggestion;
+        let dataset_len = cm.datasets.len();
+        // The suggestions for the names, before the checks of each dataset
+        let mut base_suggestions: HashMap<String, Suggestion> = HashMap::new();
+        for (dataset_num, dataset) in datasets.into_iter().enumerate() {
+            let dataset_rules = org_rules.for_dataset(&dataset.slug);
+            for column in dataset.columns {
+                let key_name = column.key_name.clone();
+                let base = base_suggestions
+                    .entry(key_name.clone())
+                    .or_insert_with(|| cm.semconv.get_suggestion(&key_name))
+                    .clone();
+                let mut suggestion =
+                    cm.semconv
+                        .check_column(base, &key_name, &column.r#type, &column.scopes);
+                let stability = cm.semconv.get_unstable(&key_name);
+                if let Some(stability) = &stability {
+                    if unstable_mode == UnstableMode::Bad {
+                        let comment = SuggestionComment::Unstable(stability.clone());
+                        suggestion = match suggestion {
+                            Suggestion::Bad(mut comments) => {
+                                comments.push(comment);
+                                Suggestion::Bad(comments)
+                            }
+                            _ => Suggestion::Bad(vec![comment]),
+                        };
+                    }
                 }
-                match health {
-                    Suggestion::Matching => dataset_health.matching += 1,
-                    Suggestion::Missing(_) => dataset_health.missing += 1,
-                    _ => dataset_health.bad += 1,
+                if !cm.semconv.is_defined(&key_name) {
+                    suggestion = rules::apply(suggestion, &dataset_rules.check(&key_name));
                 }
+                let suppressed = suggestion != Suggestion::Matching
+                    && suppressions.find(&dataset.slug, &key_name).is_some();
+                let column_type = column.r#type.clone();
+                let cu = cm
+                    .map
+                    .entry(key_name)
+                    .or_insert_with(|| ColumnUsage::new(column, stability, dataset_len));
+                cu.add_dataset(dataset_num, column_type, suggestion);
+                cu.suppressed[dataset_num] = suppressed;
             }
-            cm.dataset_health.push(dataset_health);
-            dataset_num += 1;
-        })
-        .await?;
+        }
+        cm.families = families::find_families(
+            cm.map
+                .values()
+                .filter(|c| c.suggestion != Suggestion::Matching)
+                .map(|c| c.column.key_name.as_str()),
+            MIN_FAMILY_SIZE,
+        );
+        cm.family_members = cm
+            .families
+            .iter()
+            .enumerate()
+            .flat_map(|(i, f)| f.columns.iter().map(move |name| (name.clone(), i)))
+            .collect();
+        cm.dataset_health = (0..dataset_len).map(|n| cm.health(n)).collect();
+        cm
+    }
+
+    /// Count the columns of a dataset by their suggestion in that dataset.
+    /// Each family counts once in place of its members.
+    fn health(&self, dataset_num: usize) -> DatasetHealth {
+        let mut dataset_health = DatasetHealth::new();
+        let mut count = |suggestion: &Suggestion, suppressed: bool| {
+            if suppressed {
+                dataset_health.suppressed += 1;
+                return;
+            }
+            match suggestion {
+                Suggestion::Matching => dataset_health.matching += 1,
+                Suggestion::Missing(_) => dataset_health.missing += 1,
+                _ => dataset_health.bad += 1,
+            }
+        };
+        for c in self.map.values() {
+            let Some(suggestion) = &c.suggestions[dataset_num] else {
+                continue;
+            };
+            if self.family_members.contains_key(&c.column.key_name) {
+                continue;
+            }
+            count(suggestion, c.suppressed[dataset_num]);
+        }
+        for family in &self.families {
+            if let Some((suggestion, suppressed)) = self.family_suggestion(family, dataset_num) {
+                count(suggestion, suppressed);
+            }
+        }
+        for c in self.map.values() {
+            if c.datasets[dataset_num] && c.unstable.is_some() {
+                dataset_health.unstable += 1;
+            }
+        }
+        dataset_health
+    }
 
-        bar.finish_and_clear();
-        Ok(cm)
+    /// The worst suggestion of a family's members used in a dataset, and
+    /// whether they are all suppressed there. Suppressed members only count
+    /// when they all are. `None` if the dataset has no members.
+    fn family_suggestion(
+        &self,
+        family: &ColumnFamily,
+        dataset_num: usize,
+    ) -> Option<(&Suggestion, bool)> {
+        let members = family
+            .columns
+            .iter()
+            .filter_map(|name| {
+                let c = &self.map[name];
+                Some((
+                    c.suggestions[dataset_num].as_ref()?,
+                    c.suppressed[dataset_num],
+                ))
+            })
+            .collect::<Vec<_>>();
+        let suppressed = members.iter().all(|(_, suppressed)| *suppressed);
+        members
+            .into_iter()
+            .filter(|(_, s)| suppressed || !s)
+            .map(|(suggestion, _)| suggestion)
+            .max_by_key(|suggestion| suggestion_rank(suggestion))
+            .map(|suggestion| (suggestion, suppressed))
+    }
+
+    /// The worst suggestion of a family's members across all datasets
+    fn family_merged_suggestion(&self, family: &ColumnFamily) -> &Suggestion {
+        family
+            .columns
+            .iter()
+            .map(|name| &self.map[name].suggestion)
+            .max_by_key(|suggestion| suggestion_rank(suggestion))
+            .expect("families have members")
+    }
+
+    /// Whether any member of a family is used in each dataset
+    fn family_datasets(&self, family: &ColumnFamily) -> Vec<bool> {
+        (0..self.datasets.len())
+            .map(|dataset_num| {
+                family
+                    .columns
+                    .iter()
+                    .any(|name| self.map[name].datasets[dataset_num])
+            })
+            .collect()
     }
 
     fn to_csv(&self, path: &str) -> anyhow::Result<()> {
         let file = File::create(path)?;
         let mut file = BufWriter::new(file);
-        let mut columns = self.map.values().collect::<Vec<_>>();
-        columns.sort_by(|a, b| a.column.key_name.cmp(&b.column.key_name));
+        // Families have one row in place of their members
+        let mut rows = self
+            .map
+            .values()
+            .filter(|c| !self.family_members.contains_key(&c.column.key_name))
+            .map(|c| {
+                (
+                    c.column.key_name.clone(),
+                    format!(
+                        "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",{}",
+                        c.column.key_name,
+                        c.column.r#type,
+                        c.suggestion.get_name(),
+                        c.suggestion.get_comments_string(false),
+                        c.unstable.as_deref().unwrap_or_default(),
+                        c.type_conflicts()
+                            .map(|types| self.types_as_string(&types))
+                            .unwrap_or_default(),
+                        datasets_as_string(&c.datasets)
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        rows.extend(self.families.iter().map(|f| {
+            (
+                f.pattern.clone(),
+                format!(
+                    "\"{}\",\"{}\",\"{}\",\"Family {}\",\"\",\"\",{}",
+                    f.pattern,
+                    self.map[&f.columns[0]].column.r#type,
+                    self.family_merged_suggestion(f).get_name(),
+                    self.family_recommendation(f, false),
+                    datasets_as_string(&self.family_datasets(f))
+                ),
+            )
+        }));
+        rows.sort();
         writeln!(
             file,
-            "Name,Type,SemConv,Hint,Usage,{},",
+            "Name,Type,SemConv,Hint,Stability,TypeConflict,Usage,{},",
             self.datasets.join(",")
         )?;
-        for c in columns {
-            writeln!(
-                file,
-                "\"{}\",\"{}\",\"{}\",\"{}\",{}",
-                c.column.key_name,
-                c.column.r#type,
-                c.suggestion.get_name(),
-                c.suggestion.get_comments_string(false),
-                c.datasets_as_string()
-            )?;
+        for (_, row) in rows {
+            writeln!(file, "{}", row)?;
         }
         Ok(())
     }
 
-    fn print_health(&self) {
+    fn print_health(&self, enum_report_rows: Option<&[EnumUsage]>) {
         // find the length of the longest dataset name
         let longest = "Dataset".len().max(
             self.datasets
@@ -192,27 +742,42 @@ impl ColumnUsageMap {
                 .unwrap_or(0),
         );
 
-        println!(
-            "{:>width$} {} {}  {}  {}",
+        print!(
+            "{:>width$} {} {}  {}  {}  {}  {}",
             "Dataset".bold(),
             "Match".bold().green(),
             "Miss".bold().yellow(),
             "Bad".bold().red(),
+            "Uns".bold().magenta(),
+            "Sup".bold().cyan(),
             "Score".bold().blue(),
             width = longest
         );
+        if enum_report_rows.is_some() {
+            print!("   {}", "Enum".bold().blue());
+        }
+        println!();
         for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
             let dataset_health = &self.dataset_health[dataset_num];
 
-            println!(
-                "{:>width$}  {:4} {:4} {:4} {:>5.1}%",
+            print!(
+                "{:>width$}  {:4} {:4} {:4} {:4} {:4} {:>5.1}%",
                 dataset_slug,
                 dataset_health.matching,
                 dataset_health.missing,
                 dataset_health.bad,
+                dataset_health.unstable,
+                dataset_health.suppressed,
                 dataset_health.score(),
                 width = longest
             );
+            if let Some(rows) = enum_report_rows {
+                match enum_score(rows, dataset_num) {
+                    Some(score) => print!(" {:>5.1}%", score),
+                    None => print!(" {:>6}", "-"),
+                }
+            }
+            println!();
         }
     }
 
@@ -222,7 +787,11 @@ impl ColumnUsageMap {
             return;
         }
         let longest = self.longest_column_name();
-        let mut columns = self.map.values().collect::<Vec<_>>();
+        let mut columns = self
+            .map
+            .values()
+            .filter(|c| !c.suppressed[0] && !self.family_members.contains_key(&c.column.key_name))
+            .collect::<Vec<_>>();
         columns.sort_by(|a, b| a.column.key_name.cmp(&b.column.key_name));
         println!(
             "\n{:>width$} {}",
@@ -233,7 +802,15 @@ impl ColumnUsageMap {
         for c in columns {
             match c.suggestion {
                 Suggestion::Matching => {
-                    if show_matches {
+                    if let Some(stability) = &c.unstable {
+                        println!(
+                            "{:>width$} {:7}  {}",
+                            c.column.key_name.magenta(),
+                            c.suggestion.get_name(),
+                            SuggestionComment::Unstable(stability.clone()),
+                            width = longest
+                        );
+                    } else if show_matches {
                         println!("{:>width$}", c.column.key_name.green(), width = longest);
                     }
                 }
@@ -255,6 +832,49 @@ impl ColumnUsageMap {
                 }
             }
         }
+        for family in self.reported_families(0) {
+            println!(
+                "{:>width$} {:7}  {}",
+                family.pattern.yellow(),
+                "Family",
+                self.family_recommendation(family, false),
+                width = longest
+            );
+        }
+    }
+
+    /// The families with members in a dataset that are not all suppressed
+    fn reported_families(&self, dataset_num: usize) -> Vec<&ColumnFamily> {
+        self.families
+            .iter()
+            .filter(|f| {
+                self.family_suggestion(f, dataset_num)
+                    .is_some_and(|(_, suppressed)| !suppressed)
+            })
+            .collect()
+    }
+
+    /// Recommend an existing template attribute for a family, or a new one
+    /// to define
+    fn family_recommendation(&self, family: &ColumnFamily, markdown: bool) -> String {
+        let quote = |s: &str| {
+            if markdown {
+                format!("`{}`", s)
+            } else {
+                s.to_owned()
+            }
+        };
+        let recommendation = match self.semconv.find_template(&family.prefix) {
+            Some(template) if family.trailing => format!("Use template {}", quote(template)),
+            _ if family.trailing && !family.prefix.is_empty() => {
+                format!("Define template {}", quote(&family.prefix))
+            }
+            _ => format!(
+                "Define {} and move the varying part into another attribute",
+                quote(&family.static_name)
+            ),
+        };
+        format!("{} columns, {}", family.columns.len(), recommendation)
     }
 
     fn markdown_dataset_report(&self) -> Option<(String, Vec<String>)> {
@@ -266,11 +886,13 @@ impl ColumnUsageMap {
         let dataset_slug = &self.datasets[0];
         let dataset_health = &self.dataset_health[0];
         let markdown_header = format!(
-            "## Dataset: {}\n\n - Matching: {}\n - Missing: {}\n - Bad: {}\n - Score: {:.1}%\n\n",
+            "## Dataset: {}\n\n - Matching: {}\n - Missing: {}\n - Bad: {}\n - Unstable: {}\n - Suppressed: {}\n - Score: {:.1}%\n\n",
             dataset_slug,
             dataset_health.matching,
             dataset_health.missing,
             dataset_health.bad,
+            dataset_health.unstable,
+            dataset_health.suppressed,
             dataset_health.score(),
         );
 
@@ -278,6 +900,7 @@ impl ColumnUsageMap {
         let mut columns = self
             .map
             .values()
+            .filter(|c| !c.suppressed[0] && !self.family_members.contains_key(&c.column.key_name))
             .filter_map(|c| {
                 if c.suggestion != Suggestion::Matching {
                     Some((
@@ -286,10 +909,23 @@ impl ColumnUsageMap {
                         c.suggestion.get_comments_string(true),
                     ))
                 } else {
-                    None
+                    c.unstable.as_ref().map(|stability| {
+                        (
+                            c.column.key_name.clone(),
+                            c.suggestion.get_name(),
+                            SuggestionComment::Unstable(stability.clone()).to_markdown(),
+                        )
+                    })
                 }
             })
             .collect::<Vec<_>>();
+        columns.extend(self.reported_families(0).into_iter().map(|f| {
+            (
+                f.pattern.clone(),
+                "Family".to_owned(),
+                self.family_recommendation(f, true),
+            )
+        }));
 
         if columns.is_empty() {
             return None;
@@ -333,76 +969,1132 @@ impl ColumnUsageMap {
         Some((markdown_header, markdown))
     }
 
-    fn longest_column_name(&self) -> usize {
-        "Column".len().max(
-            self.map
-                .values()
-                .map(|c| c.column.key_name.len())
-                .max()
-                .unwrap_or(0),
-        )
+    fn has_column(&self, dataset_num: usize, name: &str) -> bool {
+        self.map
+            .get(name)
+            .is_some_and(|cu| cu.datasets[dataset_num])
     }
 
-    fn print_enum_report(
-        &self,
-        enum_report_rows: &Vec<(String, Vec<String>)>,
-    ) -> anyhow::Result<()> {
-        // If there's only one dataset, print the enum comparisons
-        if self.datasets.len() != 1 {
-            return Ok(());
+    /// For every span group in the model with a span kind, find the spans of
+    /// that kind which appear to follow it and measure how often they have
+    /// each required, conditionally required and recommended attribute. Spans
+    /// follow a group when they have its first required attribute that is a
+    /// column in the dataset.
+    async fn requirement_report(&self) -> anyhow::Result<Vec<RequirementReport>> {
+        let mut groups = self
+            .semconv
+            .groups
+            .iter()
+            .filter(|(_, g)| g.r#type.as_deref() == Some("span") && g.span_kind.is_some())
+            .collect::<Vec<_>>();
+        groups.sort_by_key(|(id, _)| *id);
+
+        let mut reports = vec![];
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            for (group_id, group) in &groups {
+                let levels = group.requirement_levels();
+                let Some((anchor, _)) = levels.iter().find(|(name, level)| {
+                    *level == "required" && self.has_column(dataset_num, name)
+                }) else {
+                    continue;
+                };
+                let present = levels
+                    .iter()
+                    .filter(|(name, _)| name != anchor && self.has_column(dataset_num, name))
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>();
+                let span_kind = group.span_kind.clone().unwrap_or_default();
+                let (spans, counts) = self
+                    .source
+                    .attribute_presence(dataset_slug, &span_kind, anchor, &present)
+                    .await?;
+                if spans == 0 {
+                    continue;
+                }
+                let rows = levels
+                    .iter()
+                    .map(|(name, level)| {
+                        let count = if name == anchor {
+                            spans
+                        } else {
+                            present
+                                .iter()
+                                .position(|p| p == name)
+                                .map_or(0, |i| counts[i])
+                        };
+                        RequirementRow {
+                            attribute: name.to_string(),
+                            level: level.to_string(),
+                            percent: count as f64 / spans as f64 * 100.0,
+                        }
+                    })
+                    .collect();
+                reports.push(RequirementReport {
+                    dataset: dataset_slug.clone(),
+                    group: group_id.to_string(),
+                    span_kind,
+                    spans,
+                    rows,
+                });
+            }
         }
-        let longest = self.longest_column_name();
+        Ok(reports)
+    }
 
-        println!(
-            "\n{:>width$} {}",
-            "Column".bold(),
-            "Undefined-variants".bold(),
-            width = longest
-        );
+    fn print_requirement_report(&self, reports: &[RequirementReport]) {
+        if reports.is_empty() {
+            println!("\nNo spans following a span convention");
+            return;
+        }
+        let longest = reports
+            .iter()
+            .flat_map(|r| &r.rows)
+            .map(|row| row.attribute.len())
+            .max()
+            .unwrap_or(0)
+            .max("Attribute".len());
 
-        for (c, found_variants) in enum_report_rows {
-            if found_variants.is_empty() {
-                println!("{:>width$}", c.green(), width = longest);
-            } else {
+        for report in reports {
+            println!(
+                "\n{} {} ({}) {} spans",
+                report.dataset.bold(),
+                report.group.bold(),
+                report.span_kind,
+                report.spans
+            );
+            println!(
+                "{:>width$} {:22} {}",
+                "Attribute".bold(),
+                "Requirement".bold(),
+                "Present".bold(),
+                width = longest
+            );
+            for row in &report.rows {
+                let percent = format!("{:>6.1}%", row.percent);
+                let percent = if row.percent >= 100.0 {
+                    percent.green()
+                } else if row.level == "required" {
+                    percent.red()
+                } else {
+                    percent.yellow()
+                };
                 println!(
-                    "{:>width$} {}",
-                    c.red(),
-                    found_variants.join(", "),
+                    "{:>width$} {:22} {}",
+                    row.attribute,
+                    row.level,
+                    percent,
                     width = longest
                 );
             }
         }
-
-        Ok(())
     }
 
-    fn markdown_enum_report(
-        &self,
-        enum_report_rows: Vec<(String, Vec<String>)>,
-    ) -> anyhow::Result<(String, Vec<String>)> {
-        let dataset_slug = &self.datasets[0];
-        let markdown_header = format!("## Dataset: {}\n\n", dataset_slug);
+    /// Sample the values of the non-enum columns of model attributes, with
+    /// group-by queries, and check them against the attribute's type and any
+    /// constraints from the model or built in for well known attributes.
+    /// Columns with the wrong type are included as their values show what
+    /// was stored, e.g. integers as strings.
+    async fn value_report(&self) -> anyhow::Result<Vec<ValueReport>> {
+        let mut columns = self
+            .map
+            .values()
+            .filter_map(|c| Some((c, self.semconv.get_value_type(&c.column.key_name)?)))
+            .collect::<Vec<_>>();
+        columns.sort_by(|a, b| a.0.column.key_name.cmp(&b.0.column.key_name));
 
-        // Make the strings for each row
-        let mut row_strings = vec![];
-        let mut c_len = "Column".len();
-        let mut v_len = "Undefined-variants".len();
-        for (c, found_variants) in enum_report_rows {
-            if !found_variants.is_empty() {
-                let c_name = format!("`{}`", c);
-                c_len = c_len.max(c_name.len());
-                let variants = format!("`{}`", found_variants.join("`, `"));
-                v_len = v_len.max(variants.len());
-                row_strings.push((c_name, "Error".to_owned(), variants));
+        let mut reports = vec![];
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            // The rules depend on the column type in the dataset
+            let dataset_columns = columns
+                .iter()
+                .filter_map(|(c, (value_type, constraints))| {
+                    let column_type = c.types[dataset_num].as_deref()?;
+                    ValueRules::new(&c.column.key_name, value_type, column_type, *constraints)
+                        .transpose()
+                        .map(|rules| rules.map(|rules| (*c, rules)))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if dataset_columns.is_empty() {
+                continue;
+            }
+            let column_ids = dataset_columns
+                .iter()
+                .map(|(c, _)| c.column.key_name.clone())
+                .collect::<Vec<_>>();
+            let results = self
+                .source
+                .group_by_variants(dataset_slug, &column_ids)
+                .await?;
+
+            let mut report = ValueReport {
+                dataset: dataset_slug.clone(),
+                checked: column_ids.len(),
+                columns: vec![],
+            };
+            for (column_id, values) in results {
+                let Some((_, rules)) = dataset_columns
+                    .iter()
+                    .find(|(c, _)| c.column.key_name == column_id)
+                else {
+                    continue;
+                };
+                let mut findings = values
+                    .into_iter()
+                    .filter_map(|(value, count)| {
+                        let violation = rules.check(&value)?;
+                        Some(ValueFinding {
+                            value,
+                            count,
+                            violation,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                if findings.is_empty() {
+                    continue;
+                }
+                // most used first
+                findings.sort_by(|a, b| b.count.cmp(&a.count).then(a.value.cmp(&b.value)));
+                report.columns.push((column_id, findings));
             }
+            reports.push(report);
         }
+        Ok(reports)
+    }
 
-        let mut markdown = vec![];
-        markdown.push(format!(
-            "| {:>c_width$} | {:7} | {:v_width$} |",
-            "Column",
-            "Kind",
-            "Undefined-variants",
+    fn print_value_report(&self, reports: &[ValueReport]) {
+        if reports.is_empty() {
+            println!("\nNo columns with values to check");
+            return;
+        }
+        let longest = self.longest_column_name();
+
+        for report in reports {
+            println!(
+                "\n{} {} columns checked",
+                report.dataset.bold(),
+                report.checked
+            );
+            if report.columns.is_empty() {
+                println!("{:>width$}", "No violations".green(), width = longest);
+                continue;
+            }
+            println!(
+                "{:>width$} {}",
+                "Column".bold(),
+                "Violations".bold(),
+                width = longest
+            );
+            for (column, findings) in &report.columns {
+                let findings = findings
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!("{:>width$} {}", column.red(), findings, width = longest);
+            }
+        }
+    }
+
+    /// Sample the values of the string columns with group-by queries and
+    /// flag those that look like personal data or secrets, unless the
+    /// attribute is meant to carry them e.g. IP addresses in `client.address`.
+    /// One query is run per column so only the first `max_columns` by name
+    /// are scanned in each dataset.
+    async fn pii_report(&self, max_columns: usize) -> anyhow::Result<Vec<PiiReport>> {
+        let mut reports = vec![];
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            let mut column_ids = self
+                .map
+                .values()
+                .filter(|c| c.types[dataset_num].as_deref() == Some("string"))
+                .map(|c| c.column.key_name.clone())
+                .collect::<Vec<_>>();
+            if column_ids.is_empty() {
+                continue;
+            }
+            column_ids.sort();
+            let skipped = column_ids.len().saturating_sub(max_columns);
+            column_ids.truncate(max_columns);
+            let results = self
+                .source
+                .group_by_variants(dataset_slug, &column_ids)
+                .await?;
+
+            let mut findings = vec![];
+            for (column_id, values) in results {
+                // Most used value of each kind is the sample
+                let mut found: BTreeMap<PiiKind, PiiSample> = BTreeMap::new();
+                for (value, count) in values {
+                    let Some(kind) = pii::detect(&value) else {
+                        continue;
+                    };
+                    if kind.is_expected_in(&column_id) {
+                        continue;
+                    }
+                    let sample = found.entry(kind).or_default();
+                    sample.count += count;
+                    if sample.value.is_empty() || count > sample.value_count {
+                        sample.value = value;
+                        sample.value_count = count;
+                    }
+                }
+                findings.extend(found.into_iter().map(|(kind, sample)| PiiFinding {
+                    column: column_id.clone(),
+                    kind,
+                    count: sample.count,
+                    sample: pii::mask(&sample.value),
+                }));
+            }
+            findings.sort_by(|a, b| a.column.cmp(&b.column).then(a.kind.cmp(&b.kind)));
+            reports.push(PiiReport {
+                dataset: dataset_slug.clone(),
+                scanned: column_ids.len(),
+                skipped,
+                findings,
+            });
+        }
+        Ok(reports)
+    }
+
+    fn print_pii_report(&self, reports: &[PiiReport]) {
+        if reports.is_empty() {
+            println!("\nNo string columns to scan");
+            return;
+        }
+        let longest = self.longest_column_name();
+
+        for report in reports {
+            println!(
+                "\n{} {} columns scanned",
+                report.dataset.bold(),
+                report.scanned
+            );
+            if report.skipped > 0 {
+                println!(
+                    "{}",
+                    format!(
+                        "{} string columns not scanned, raise --max-pii-columns to include them",
+                        report.skipped
+                    )
+                    .yellow()
+                );
+            }
+            if report.findings.is_empty() {
+                println!("{:>width$}", "No findings".green(), width = longest);
+                continue;
+            }
+            println!(
+                "{:>width$} {:11} {:>8} {}",
+                "Column".bold(),
+                "Kind".bold(),
+                "Events".bold(),
+                "Sample".bold(),
+                width = longest
+            );
+            for finding in &report.findings {
+                println!(
+                    "{:>width$} {:11} {:>8} {}",
+                    finding.column.red(),
+                    finding.kind.to_string(),
+                    finding.count,
+                    finding.sample,
+                    width = longest
+                );
+            }
+        }
+    }
+
+    fn markdown_pii_report(&self, reports: &[PiiReport]) -> Option<(String, Vec<String>)> {
+        let findings = reports
+            .iter()
+            .flat_map(|r| r.findings.iter().map(move |f| (&r.dataset, f)))
+            .map(|(dataset, f)| {
+                (
+                    dataset.clone(),
+                    format!("`{}`", f.column),
+                    f.kind.to_string(),
+                    f.count.to_string(),
+                    format!("`{}`", f.sample),
+                )
+            })
+            .collect::<Vec<_>>();
+        if findings.is_empty() {
+            return None;
+        }
+        let markdown_header = format!(
+            "## Datasets: {}\n\n",
+            reports
+                .iter()
+                .map(|r| r.dataset.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let d_len = findings
+            .iter()
+            .map(|f| f.0.len())
+            .max()
+            .unwrap_or(0)
+            .max("Dataset".len());
+        let c_len = findings
+            .iter()
+            .map(|f| f.1.len())
+            .max()
+            .unwrap_or(0)
+            .max("Column".len());
+        let s_len = findings
+            .iter()
+            .map(|f| f.4.len())
+            .max()
+            .unwrap_or(0)
+            .max("Sample".len());
+
+        let mut markdown = vec![];
+        markdown.push(format!(
+            "| {:d_width$} | {:c_width$} | {:11} | {:>8} | {:s_width$} |",
+            "Dataset",
+            "Column",
+            "Kind",
+            "Events",
+            "Sample",
+            d_width = d_len,
+            c_width = c_len,
+            s_width = s_len
+        ));
+        markdown.push(format!(
+            "| :{} | :{} | :---------- | -------: | :{} |",
+            "-".repeat(d_len - 1),
+            "-".repeat(c_len - 1),
+            "-".repeat(s_len - 1),
+        ));
+        for f in findings {
+            markdown.push(format!(
+                "| {:d_width$} | {:c_width$} | {:11} | {:>8} | {:s_width$} |",
+                f.0,
+                f.1,
+                f.2,
+                f.3,
+                f.4,
+                d_width = d_len,
+                c_width = c_len,
+                s_width = s_len
+            ));
+        }
+
+        Some((markdown_header, markdown))
+    }
+
+    /// Count the distinct values of columns expected to be low cardinality:
+    /// matching enums, well known route-like attributes and any extra columns
+    /// given. Those over the threshold are reported with their most used
+    /// values.
+    async fn cardinality_report(
+        &self,
+        max_cardinality: u64,
+        extra_columns: &[String],
+    ) -> anyhow::Result<Vec<CardinalityReport>> {
+        let mut reports = vec![];
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            let mut column_ids = self
+                .map
+                .values()
+                .filter(|c| c.datasets[dataset_num])
+                .map(|c| &c.column.key_name)
+                .filter(|name| {
+                    LOW_CARDINALITY.contains(&name.as_str())
+                        || extra_columns.contains(*name)
+                        // Only model enums reach the slower suggestion check
+                        || (self.semconv.get_enum(name).is_some()
+                            && self.semconv.get_suggestion(name) == Suggestion::Matching)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if column_ids.is_empty() {
+                continue;
+            }
+            column_ids.sort();
+            let counts = self
+                .source
+                .distinct_counts(dataset_slug, &column_ids)
+                .await?;
+            let mut rows = counts
+                .into_iter()
+                .filter(|(_, distinct)| *distinct > max_cardinality)
+                .map(|(column, distinct)| CardinalityRow {
+                    column,
+                    distinct,
+                    top: vec![],
+                })
+                .collect::<Vec<_>>();
+            if !rows.is_empty() {
+                let high = rows.iter().map(|r| r.column.clone()).collect::<Vec<_>>();
+                for (column, variants) in self.source.group_by_variants(dataset_slug, &high).await?
+                {
+                    let Some(row) = rows.iter_mut().find(|r| r.column == column) else {
+                        continue;
+                    };
+                    let mut top = variants.into_iter().collect::<Vec<_>>();
+                    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                    top.truncate(TOP_VALUES);
+                    row.top = top;
+                }
+            }
+            // highest cardinality first
+            rows.sort_by(|a, b| b.distinct.cmp(&a.distinct).then(a.column.cmp(&b.column)));
+            reports.push(CardinalityReport {
+                dataset: dataset_slug.clone(),
+                checked: column_ids.len(),
+                rows,
+            });
+        }
+        Ok(reports)
+    }
+
+    fn print_cardinality_report(&self, reports: &[CardinalityReport], max_cardinality: u64) {
+        if reports.is_empty() {
+            println!("\nNo low cardinality columns to check");
+            return;
+        }
+        let longest = self.longest_column_name();
+
+        for report in reports {
+            println!(
+                "\n{} {} columns checked, max cardinality {}",
+                report.dataset.bold(),
+                report.checked,
+                max_cardinality
+            );
+            if report.rows.is_empty() {
+                println!(
+                    "{:>width$}",
+                    "No high cardinality columns".green(),
+                    width = longest
+                );
+                continue;
+            }
+            println!(
+                "{:>width$} {:>8} {}",
+                "Column".bold(),
+                "Distinct".bold(),
+                "Top-values".bold(),
+                width = longest
+            );
+            for row in &report.rows {
+                let top = row
+                    .top
+                    .iter()
+                    .map(|(value, count)| format!("{} ({})", value, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "{:>width$} {:>8} {}",
+                    row.column.red(),
+                    row.distinct,
+                    top,
+                    width = longest
+                );
+            }
+        }
+    }
+
+    /// Group the columns of all datasets whose names normalise to the same
+    /// words, and recommend the model's name for them or else the spelling
+    /// used by the most datasets.
+    fn synonym_report(&self) -> Vec<SynonymGroup> {
+        let model_names = self
+            .semconv
+            .attribute_map
+            .keys()
+            .map(|name| (synonyms::normalise(name), name))
+            .collect::<BTreeMap<_, _>>();
+        synonyms::find_synonyms(self.map.keys().map(|k| k.as_str()))
+            .into_iter()
+            .map(|names| {
+                let spellings = names
+                    .into_iter()
+                    .map(|name| {
+                        let datasets = self.map[&name].datasets.clone();
+                        (name, datasets)
+                    })
+                    .collect::<Vec<_>>();
+                let model_name = spellings
+                    .iter()
+                    .map(|(name, _)| name)
+                    .find(|name| self.map[*name].suggestion == Suggestion::Matching)
+                    .or_else(|| {
+                        model_names
+                            .get(&synonyms::normalise(&spellings[0].0))
+                            .copied()
+                    });
+                let (canonical, from_model) = match model_name {
+                    Some(name) => (name.clone(), true),
+                    None => {
+                        let used = |datasets: &[bool]| datasets.iter().filter(|d| **d).count();
+                        let (name, _) = spellings
+                            .iter()
+                            .max_by(|a, b| used(&a.1).cmp(&used(&b.1)).then(b.0.cmp(&a.0)))
+                            .expect("synonyms have more than one spelling");
+                        (name.clone(), false)
+                    }
+                };
+                SynonymGroup {
+                    canonical,
+                    from_model,
+                    spellings,
+                }
+            })
+            .collect()
+    }
+
+    fn print_synonym_report(&self, groups: &[SynonymGroup]) {
+        if groups.is_empty() {
+            println!("\nNo synonyms found");
+            return;
+        }
+        let longest = self.longest_column_name();
+
+        for group in groups {
+            println!(
+                "\n{} {}",
+                group.canonical.bold(),
+                if group.from_model {
+                    "(semantic convention)"
+                } else {
+                    "(most used)"
+                }
+            );
+            for (name, datasets) in &group.spellings {
+                let datasets = self
+                    .datasets
+                    .iter()
+                    .zip(datasets)
+                    .filter(|(_, used)| **used)
+                    .map(|(d, _)| d.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let name = if *name == group.canonical {
+                    name.green()
+                } else {
+                    name.yellow()
+                };
+                println!("{:>width$} {}", name, datasets, width = longest);
+            }
+        }
+    }
+
+    /// Each type with the datasets holding it e.g.
+    /// `integer: dataset1; string: dataset2, dataset3`
+    fn types_as_string(&self, types: &BTreeMap<&str, Vec<usize>>) -> String {
+        types
+            .iter()
+            .map(|(t, dataset_nums)| {
+                let datasets = dataset_nums
+                    .iter()
+                    .map(|n| self.datasets[*n].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}: {}", t, datasets)
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// List each family once with the datasets using its columns
+    fn print_families(&self) {
+        if self.families.is_empty() {
+            return;
+        }
+        let longest = "Family".len().max(
+            self.families
+                .iter()
+                .map(|f| f.pattern.len())
+                .max()
+                .unwrap_or(0),
+        );
+
+        println!(
+            "\n{:>width$} {}",
+            "Family".bold(),
+            "Recommendation".bold(),
+            width = longest
+        );
+        for family in &self.families {
+            let datasets = self
+                .datasets
+                .iter()
+                .zip(self.family_datasets(family))
+                .filter(|(_, used)| *used)
+                .map(|(d, _)| d.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!(
+                "{:>width$} {} in {}",
+                family.pattern.yellow(),
+                self.family_recommendation(family, false),
+                datasets,
+                width = longest
+            );
+        }
+    }
+
+    fn print_type_conflicts(&self) {
+        let mut columns = self
+            .map
+            .values()
+            .filter_map(|c| Some((&c.column.key_name, c.type_conflicts()?)))
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            return;
+        }
+        columns.sort_by(|a, b| a.0.cmp(b.0));
+        let longest = self.longest_column_name();
+
+        println!(
+            "\n{:>width$} {}",
+            "Column".bold(),
+            "Type-conflicts".bold(),
+            width = longest
+        );
+        for (column, types) in columns {
+            println!(
+                "{:>width$} {}",
+                column.red(),
+                self.types_as_string(&types),
+                width = longest
+            );
+        }
+    }
+
+    /// The suppressions that still apply with the number of columns each
+    /// suppressed, counting a column once per dataset
+    fn suppression_counts<'a>(
+        &self,
+        suppressions: &'a Suppressions,
+    ) -> Vec<(&'a Suppression, usize)> {
+        let mut counts = suppressions
+            .active()
+            .into_iter()
+            .map(|s| (s, 0))
+            .collect::<Vec<_>>();
+        for c in self.map.values() {
+            for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+                if !c.suppressed[dataset_num] {
+                    continue;
+                }
+                let Some(suppression) = suppressions.find(dataset_slug, &c.column.key_name) else {
+                    continue;
+                };
+                if let Some((_, count)) = counts
+                    .iter_mut()
+                    .find(|(s, _)| std::ptr::eq(*s, suppression))
+                {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// The Missing and Bad columns of each dataset that are not suppressed,
+    /// with the suggestion the dataset's health counted
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            let mut dataset_findings = vec![];
+            for c in self.map.values() {
+                let Some(suggestion) = &c.suggestions[dataset_num] else {
+                    continue;
+                };
+                if !c.suppressed[dataset_num]
+                    && *suggestion != Suggestion::Matching
+                    && !self.family_members.contains_key(&c.column.key_name)
+                {
+                    dataset_findings.push(Finding {
+                        dataset: dataset_slug.clone(),
+                        column: c.column.key_name.clone(),
+                        suggestion: suggestion.get_name(),
+                        comments: suggestion.get_comments_string(false),
+                    });
+                }
+            }
+            // Each family is one finding
+            for family in self.reported_families(dataset_num) {
+                if let Some((suggestion, _)) = self.family_suggestion(family, dataset_num) {
+                    if *suggestion != Suggestion::Matching {
+                        dataset_findings.push(Finding {
+                            dataset: dataset_slug.clone(),
+                            column: family.pattern.clone(),
+                            suggestion: suggestion.get_name(),
+                            comments: format!(
+                                "Family {}",
+                                self.family_recommendation(family, false)
+                            ),
+                        });
+                    }
+                }
+            }
+            dataset_findings.sort_by(|a, b| a.column.cmp(&b.column));
+            findings.extend(dataset_findings);
+        }
+        findings
+    }
+
+    fn print_baseline_report(&self, comparison: &BaselineComparison) {
+        let longest = "Dataset".len().max(
+            self.datasets
+                .iter()
+                .map(|dataset_slug| dataset_slug.len())
+                .max()
+                .unwrap_or(0),
+        );
+        let print_findings = |title: &str, findings: &[&Finding]| {
+            let longest_column = "Column"
+                .len()
+                .max(findings.iter().map(|f| f.column.len()).max().unwrap_or(0));
+            println!("\n{}", title.bold());
+            println!(
+                "{:>width$} {:column_width$} {}",
+                "Dataset".bold(),
+                "Column".bold(),
+                "Suggestion".bold(),
+                width = longest,
+                column_width = longest_column
+            );
+            for finding in findings {
+                println!(
+                    "{:>width$} {:column_width$} {:7}  {}",
+                    finding.dataset,
+                    finding.column,
+                    finding.suggestion,
+                    finding.comments,
+                    width = longest,
+                    column_width = longest_column
+                );
+            }
+        };
+        if comparison.new.is_empty() {
+            println!("\n{}", "No new findings since the baseline".green());
+        } else {
+            print_findings("New since baseline", &comparison.new);
+        }
+        if !comparison.fixed.is_empty() {
+            print_findings("Fixed since baseline", &comparison.fixed);
+        }
+    }
+
+    /// Every attribute and template in the model, optionally only those in a
+    /// namespace or defined in a model root, with the datasets using it.
+    /// Columns use a template when they are named `<template>.<key>`.
+    fn coverage_report(&self, namespace: Option<&str>, root: Option<&str>) -> Vec<CoverageRow> {
+        let attributes = self
+            .semconv
+            .attribute_map
+            .iter()
+            .map(|(name, attribute)| (name, attribute, false))
+            .chain(
+                self.semconv
+                    .templates
+                    .iter()
+                    .map(|(name, attribute)| (name, attribute, true)),
+            )
+            // builtins have no attribute
+            .filter(|(_, attribute, _)| attribute.is_some())
+            .filter(|(name, _, _)| {
+                namespace.is_none_or(|ns| {
+                    *name == ns || name.strip_prefix(ns).is_some_and(|n| n.starts_with('.'))
+                })
+            })
+            .filter(|(name, _, _)| {
+                root.is_none_or(|root| {
+                    self.semconv.attribute_roots.get(*name).map(|r| r.as_str()) == Some(root)
+                })
+            });
+
+        let mut rows = attributes
+            .map(|(name, _, template)| {
+                let datasets = if template {
+                    let prefix = format!("{}.", name);
+                    (0..self.datasets.len())
+                        .map(|dataset_num| {
+                            self.map.values().any(|c| {
+                                c.datasets[dataset_num] && c.column.key_name.starts_with(&prefix)
+                            })
+                        })
+                        .collect()
+                } else {
+                    self.map
+                        .get(name)
+                        .map_or(vec![false; self.datasets.len()], |c| c.datasets.clone())
+                };
+                CoverageRow {
+                    attribute: name.clone(),
+                    template,
+                    datasets,
+                }
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.attribute.cmp(&b.attribute));
+        rows
+    }
+
+    fn print_coverage_report(&self, rows: &[CoverageRow]) {
+        if rows.is_empty() {
+            println!("\nNo model attributes to report");
+            return;
+        }
+        let longest = rows
+            .iter()
+            .map(|r| r.attribute.len() + if r.template { 5 } else { 0 })
+            .max()
+            .unwrap_or(0)
+            .max("Attribute".len());
+
+        println!(
+            "\n{:>width$} {}",
+            "Attribute".bold(),
+            "Datasets".bold(),
+            width = longest
+        );
+        let mut unused = 0;
+        for row in rows {
+            let name = if row.template {
+                format!("{}.<key>", row.attribute)
+            } else {
+                row.attribute.clone()
+            };
+            let datasets = self
+                .datasets
+                .iter()
+                .zip(&row.datasets)
+                .filter(|(_, used)| **used)
+                .map(|(d, _)| d.as_str())
+                .collect::<Vec<_>>();
+            if datasets.is_empty() {
+                unused += 1;
+                println!("{:>width$} {}", name.red(), "unused".red(), width = longest);
+            } else {
+                println!(
+                    "{:>width$} {}",
+                    name.green(),
+                    datasets.join(", "),
+                    width = longest
+                );
+            }
+        }
+        println!(
+            "\n{} of {} model attributes are not used by any dataset",
+            unused,
+            rows.len()
+        );
+    }
+
+    /// Roll up the columns of all datasets by namespace
+    fn namespace_tree(&self) -> NamespaceTree {
+        let mut tree = NamespaceTree::default();
+        let datasets = |used: &[bool]| {
+            self.datasets
+                .iter()
+                .zip(used)
+                .filter(|(_, used)| **used)
+                .map(|(d, _)| d.as_str())
+                .collect::<Vec<_>>()
+        };
+        for c in self.map.values() {
+            if self.family_members.contains_key(&c.column.key_name) {
+                continue;
+            }
+            tree.add(
+                &c.column.key_name,
+                &c.suggestion,
+                &datasets(&c.datasets),
+                |ns| self.semconv.prefixes.contains(ns),
+            );
+        }
+        // Each family counts once
+        for family in &self.families {
+            tree.add(
+                &family.pattern,
+                self.family_merged_suggestion(family),
+                &datasets(&self.family_datasets(family)),
+                |ns| self.semconv.prefixes.contains(ns),
+            );
+        }
+        tree
+    }
+
+    fn print_namespace_tree(&self, tree: &NamespaceTree) {
+        if tree.roots.is_empty() {
+            println!("\nNo namespaced columns");
+            return;
+        }
+        fn longest(nodes: &BTreeMap<String, NamespaceNode>, depth: usize) -> usize {
+            nodes
+                .iter()
+                .map(|(name, node)| {
+                    (depth * 2 + name.len()).max(longest(&node.children, depth + 1))
+                })
+                .max()
+                .unwrap_or(0)
+        }
+        fn print_nodes(nodes: &BTreeMap<String, NamespaceNode>, depth: usize, width: usize) {
+            for (name, node) in nodes {
+                let label = format!("{}{}", "  ".repeat(depth), name);
+                let label = if node.known {
+                    label.normal()
+                } else {
+                    label.yellow()
+                };
+                println!(
+                    "{:width$} {:5} {:4} {:4} {:>8}",
+                    label,
+                    node.matching,
+                    node.missing,
+                    node.bad,
+                    node.datasets.len(),
+                    width = width
+                );
+                print_nodes(&node.children, depth + 1, width);
+            }
+        }
+        let width = longest(&tree.roots, 0).max("Namespace".len());
+        println!(
+            "\n{:width$} {} {} {}  {}",
+            "Namespace".bold(),
+            "Match".bold().green(),
+            "Miss".bold().yellow(),
+            "Bad".bold().red(),
+            "Datasets".bold(),
+            width = width
+        );
+        print_nodes(&tree.roots, 0, width);
+    }
+
+    fn write_namespace_tree(&self, tree: &NamespaceTree, path: &str) -> anyhow::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, tree)?;
+        Ok(())
+    }
+
+    fn longest_column_name(&self) -> usize {
+        "Column".len().max(
+            self.map
+                .values()
+                .map(|c| c.column.key_name.len())
+                .max()
+                .unwrap_or(0),
+        )
+    }
+
+    fn print_enum_report(&self, enum_report_rows: &[EnumUsage]) -> anyhow::Result<()> {
+        // If there's only one dataset, print the enum comparisons
+        if self.datasets.len() != 1 {
+            return Ok(());
+        }
+        let longest = self.longest_column_name();
+
+        println!(
+            "\n{:>width$} {}",
+            "Column".bold(),
+            "Undefined-variants".bold(),
+            width = longest
+        );
+
+        for (c, found_variants) in enum_report_rows
+            .iter()
+            .filter_map(|r| Some((&r.column, r.undefined[0].as_ref()?)))
+        {
+            match worst_kind(found_variants) {
+                None => println!("{:>width$}", c.green(), width = longest),
+                Some(kind) => {
+                    let variants = found_variants
+                        .iter()
+                        .map(|v| v.kind.colorize(&v.to_string()).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    println!("{:>width$} {}", kind.colorize(c), variants, width = longest);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the enum column by dataset matrix. Each cell has the undefined
+    /// variants, `ok` if there are none, or is empty if the dataset does not
+    /// have the column. A `.json` path writes a JSON object of column to
+    /// dataset to undefined variants instead.
+    fn write_enum_report(&self, enum_report_rows: &[EnumUsage], path: &str) -> anyhow::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        if path.ends_with(".json") {
+            let matrix = enum_report_rows
+                .iter()
+                .map(|r| {
+                    let datasets = self
+                        .datasets
+                        .iter()
+                        .zip(&r.undefined)
+                        .filter_map(|(d, u)| Some((d.clone(), u.clone()?)))
+                        .collect::<BTreeMap<_, _>>();
+                    (r.column.clone(), datasets)
+                })
+                .collect::<BTreeMap<_, _>>();
+            serde_json::to_writer_pretty(&mut file, &matrix)?;
+            return Ok(());
+        }
+        writeln!(file, "Column,{},", self.datasets.join(","))?;
+        for r in enum_report_rows {
+            let cells = r
+                .undefined
+                .iter()
+                .map(|u| match u {
+                    None => "".to_owned(),
+                    Some(v) if v.is_empty() => "ok".to_owned(),
+                    Some(v) => format!("\"{}\"", join_variants(v, "; ").replace('"', "\"\"")),
+                })
+                .collect::<Vec<_>>();
+            writeln!(file, "\"{}\",{},", r.column, cells.join(","))?;
+        }
+        Ok(())
+    }
+
+    fn markdown_enum_report(
+        &self,
+        enum_report_rows: Vec<EnumUsage>,
+    ) -> Option<(String, Vec<String>)> {
+        // If there's only one dataset, print the enum comparisons
+        if self.datasets.len() != 1 || enum_report_rows.is_empty() {
+            return None;
+        }
+        let dataset_slug = &self.datasets[0];
+        let markdown_header = format!("## Dataset: {}\n\n", dataset_slug);
+
+        // Make the strings for each row
+        let mut row_strings = vec![];
+        let mut c_len = "Column".len();
+        let mut v_len = "Undefined-variants".len();
+        for r in enum_report_rows {
+            let (c, Some(found_variants)) = (r.column, &r.undefined[0]) else {
+                continue;
+            };
+            if let Some(kind) = worst_kind(found_variants) {
+                let c_name = format!("`{}`", c);
+                c_len = c_len.max(c_name.len());
+                let variants = found_variants
+                    .iter()
+                    .map(|v| v.to_markdown())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                v_len = v_len.max(variants.len());
+                row_strings.push((c_name, kind.to_string(), variants));
+            }
+        }
+
+        let mut markdown = vec![];
+        markdown.push(format!(
+            "| {:>c_width$} | {:7} | {:v_width$} |",
+            "Column",
+            "Kind",
+            "Undefined-variants",
             c_width = c_len,
             v_width = v_len
         ));
@@ -426,61 +2118,61 @@ impl ColumnUsageMap {
             ));
         }
 
-        Ok((markdown_header, markdown))
+        Some((markdown_header, markdown))
     }
 
-    async fn enum_report(&self) -> anyhow::Result<Vec<(String, Vec<String>)>> {
-        let mut v_results = Vec::new();
-
-        // If there's only one dataset, print the enum comparisons
-        if self.datasets.len() != 1 {
-            return Ok(v_results);
-        }
+    async fn enum_report(&self) -> anyhow::Result<Vec<EnumUsage>> {
         let mut columns = self.map.values().collect::<Vec<_>>();
         columns.retain(|c| {
-            if c.suggestion == Suggestion::Matching {
-                if let Some(Some(a)) = self.semconv.attribute_map.get(&c.column.key_name) {
-                    if let Some(semconv::Type::Complex(_)) = &a.r#type {
-                        return true;
-                    }
-                }
-            }
-            false
+            c.suggestion == Suggestion::Matching
+                && self.semconv.get_enum(&c.column.key_name).is_some()
         });
 
         if columns.is_empty() {
             println!("\nNo columns with enum types");
-            return Ok(v_results);
+            return Ok(vec![]);
         }
+        columns.sort_by(|a, b| a.column.key_name.cmp(&b.column.key_name));
 
-        let column_ids = columns
+        let mut v_results = columns
             .iter()
-            .map(|c| c.column.key_name.clone())
+            .map(|c| EnumUsage {
+                column: c.column.key_name.clone(),
+                undefined: vec![None; self.datasets.len()],
+            })
             .collect::<Vec<_>>();
 
-        let hc = honeycomb_client::get_honeycomb(&["columns", "createDatasets", "queries"])
-            .await?
-            .context("API key does not have required access")?;
+        for (dataset_num, dataset_slug) in self.datasets.iter().enumerate() {
+            let column_ids = columns
+                .iter()
+                .filter(|c| c.datasets[dataset_num])
+                .map(|c| c.column.key_name.clone())
+                .collect::<Vec<_>>();
+            if column_ids.is_empty() {
+                continue;
+            }
+            let results = self
+                .source
+                .group_by_variants(dataset_slug, &column_ids)
+                .await?;
 
-        let range_seconds = self.max_last_written_days * 24 * 60 * 60;
-        let mut results = hc
-            .get_all_group_by_variants(&self.datasets[0], &column_ids, range_seconds)
-            .await?;
-        results.sort();
-
-        for (c, mut found_variants) in results {
-            if let Some(Some(a)) = self.semconv.attribute_map.get(&c) {
-                if let Some(semconv::Type::Complex(atype)) = &a.r#type {
-                    let defined_variants = atype.get_simple_variants();
-                    // trim all whitespace from found_variants
-                    // remove all defined enums from found_enums
-                    found_variants = found_variants
-                        .into_iter()
-                        .map(|e| e.trim().to_owned())
-                        .filter(|e| !defined_variants.contains(e))
-                        .collect();
-                    v_results.push((c, found_variants));
-                }
+            for (c, found_variants) in results {
+                let Some(atype) = self.semconv.get_enum(&c) else {
+                    continue;
+                };
+                let Some(row) = v_results.iter_mut().find(|r| r.column == c) else {
+                    continue;
+                };
+                // remove all defined enums from found_enums, whitespace
+                // around a string variant makes it undefined
+                let mut undefined = found_variants
+                    .into_iter()
+                    .filter(|(e, _)| !atype.is_defined(e))
+                    .map(|(value, count)| UndefinedVariant::new(atype, value, count))
+                    .collect::<Vec<_>>();
+                // most used first
+                undefined.sort_by(|a, b| b.count.cmp(&a.count).then(a.value.cmp(&b.value)));
+                row.undefined[dataset_num] = Some(undefined);
             }
         }
 
@@ -499,7 +2191,8 @@ struct Args {
     /// Model paths
     ///
     /// Provide one or more paths to the root of semantic convention
-    /// model directories.
+    /// model directories, or to a resolved registry file produced by
+    /// `weaver registry resolve`.
     #[arg(short, long, required = true, num_args(1..))]
     model: Vec<String>,
 
@@ -521,7 +2214,8 @@ struct Args {
     /// Max last written days
     ///
     /// The maximum number of days since a dataset was last written to. This
-    /// defaults to 30 days.
+    /// defaults to 30 days. Queries, e.g. for enum variants, cover this many
+    /// days up to 7 days.
     #[arg(short, long, default_value_t = 30)]
     last_written_days: usize,
 
@@ -531,6 +2225,14 @@ struct Args {
     #[arg(short, long, default_value_t = false)]
     enums: bool,
 
+    /// Enum output file path
+    ///
+    /// Provide a path to the enum column by dataset report. This is only
+    /// used with `--enums` when more than one dataset is included. Use a
+    /// `.json` extension for JSON rather than CSV.
+    #[arg(long, default_value_t = String::from("hh_enum_report.csv"))]
+    enum_output: String,
+
     /// Show matches
     ///
     /// Show all matching attributes when analyzing a single dataset.
@@ -543,6 +2245,222 @@ struct Args {
     /// repository owner and name e.g. "jerbly/honey-health".
     #[arg(short, long, required = false)]
     github_issue: Option<String>,
+
+    /// Unstable attributes
+    ///
+    /// How to treat columns matching experimental or development semantic
+    /// conventions. These names may change in future releases.
+    #[arg(short, long, value_enum, default_value_t = UnstableMode::Warning)]
+    unstable: UnstableMode,
+
+    /// From snapshot
+    ///
+    /// Run the reports from a snapshot file saved with the `snapshot`
+    /// command instead of calling the Honeycomb API. Snapshots hold the
+    /// columns, their scopes and the enum variants only, so `--values`,
+    /// `--pii` and `--requirements` cannot be used and `--cardinality` only
+    /// counts the saved enum variants.
+    #[arg(short, long, required = false, conflicts_with = "otlp")]
+    from_snapshot: Option<String>,
+
+    /// OTLP JSON files
+    ///
+    /// Run the reports from one or more files written by the OpenTelemetry
+    /// Collector `file` exporter instead of calling the Honeycomb API. Each
+    /// `service.name` is treated as a dataset.
+    #[arg(long, required = false, num_args(1..))]
+    otlp: Option<Vec<String>>,
+
+    /// Scope check
+    ///
+    /// Query Honeycomb to find whether columns matching span and span event
+    /// attributes were set on spans, span events or links, and report those
+    /// in the wrong place. OTLP files always include the scopes.
+    #[arg(long, default_value_t = false)]
+    scopes: bool,
+
+    /// Requirement levels
+    ///
+    /// Query Honeycomb to measure how often spans following a span
+    /// convention, e.g. HTTP server spans, include each of its required,
+    /// conditionally required and recommended attributes.
+    #[arg(short, long, default_value_t = false, conflicts_with = "from_snapshot")]
+    requirements: bool,
+
+    /// Value check
+    ///
+    /// Sample the values of matching columns that are not enums and check
+    /// them against the attribute's type, e.g. integers stored as strings,
+    /// and against value constraints such as port and status code ranges.
+    /// Models can add constraints with an `annotations` `honey_health` key.
+    #[arg(long, default_value_t = false, conflicts_with = "from_snapshot")]
+    values: bool,
+
+    /// PII scan
+    ///
+    /// Sample the values of string columns and flag those that look like
+    /// emails, credit card numbers, JWTs, bearer tokens, AWS keys or IP
+    /// addresses in attributes not meant to carry them.
+    #[arg(long, default_value_t = false, conflicts_with = "from_snapshot")]
+    pii: bool,
+
+    /// Max PII columns
+    ///
+    /// The most string columns to scan in each dataset, in name order. One
+    /// query is run per column.
+    #[arg(long, default_value_t = 100)]
+    max_pii_columns: usize,
+
+    /// Cardinality check
+    ///
+    /// Count the distinct values of columns expected to be low cardinality,
+    /// i.e. enums, span names, routes and operation names, and report those
+    /// over `--max-cardinality` with their most used values.
+    #[arg(long, default_value_t = false)]
+    cardinality: bool,
+
+    /// Max cardinality
+    ///
+    /// The number of distinct values above which a low cardinality column is
+    /// reported.
+    #[arg(long, default_value_t = 100)]
+    max_cardinality: u64,
+
+    /// Low cardinality columns
+    ///
+    /// Provide extra columns to include in the cardinality check.
+    #[arg(long, required = false, num_args(1..))]
+    low_cardinality: Vec<String>,
+
+    /// Synonyms
+    ///
+    /// Group the columns of all datasets that are spellings of the same name,
+    /// e.g. `user_id`, `userId` and `user.id`, show which datasets use each
+    /// and recommend a canonical name.
+    #[arg(long, default_value_t = false)]
+    synonyms: bool,
+
+    /// Coverage report
+    ///
+    /// List every attribute and template in the model with the datasets
+    /// using it, marking those no dataset uses.
+    #[arg(long, default_value_t = false)]
+    coverage: bool,
+
+    /// Coverage namespace
+    ///
+    /// Only include model attributes in this namespace in the coverage
+    /// report e.g. `http`.
+    #[arg(long, required = false)]
+    coverage_namespace: Option<String>,
+
+    /// Coverage model root
+    ///
+    /// Only include model attributes defined in this `--model` path in the
+    /// coverage report.
+    #[arg(long, required = false)]
+    coverage_root: Option<String>,
+
+    /// Namespace tree
+    ///
+    /// Roll up the columns of all datasets by namespace, e.g. `http` and
+    /// `http.request`, with counts of matching, missing and bad columns and
+    /// the datasets contributing. Namespaces not in the model are
+    /// highlighted.
+    #[arg(long, default_value_t = false)]
+    namespaces: bool,
+
+    /// Namespace output file path
+    ///
+    /// Provide a path to the JSON namespace tree written with
+    /// `--namespaces`.
+    #[arg(long, default_value_t = String::from("hh_namespaces.json"))]
+    namespace_output: String,
+
+    /// Max namespace depth
+    ///
+    /// The number of namespace segments, e.g. 2 for `http.request.method`,
+    /// above which a column is reported as a `DeepNamespace`.
+    #[arg(long, default_value_t = 5)]
+    max_namespace_depth: usize,
+
+    /// Max extra namespace depth
+    ///
+    /// The number of namespace segments a column can add to the nearest
+    /// namespace in the model before it is reported as a `DeepNamespace`.
+    #[arg(long, default_value_t = 3)]
+    max_extra_depth: usize,
+
+    /// Disabled naming rules
+    ///
+    /// Switch off naming rules by id, e.g. `--disable-rule array-plural
+    /// max-length`. Rules can also be switched off, or their severity
+    /// changed, in the `--rules` file.
+    #[arg(long, required = false, num_args(1..), value_parser = naming_rule_parser())]
+    disable_rule: Vec<NamingRule>,
+
+    /// Max name length
+    ///
+    /// The number of characters above which a column name is reported as
+    /// `TooLong`.
+    #[arg(long, default_value_t = 255)]
+    max_name_length: usize,
+
+    /// Write baseline
+    ///
+    /// Provide a path to write the current Missing and Bad columns of each
+    /// dataset to as a JSON baseline.
+    #[arg(long)]
+    write_baseline: Option<String>,
+
+    /// Baseline
+    ///
+    /// Provide a path to a baseline written with `--write-baseline` to report
+    /// only the findings that are not in it, and those it has that are fixed.
+    /// Exits with an error when there are new findings.
+    #[arg(long)]
+    baseline: Option<String>,
+
+    /// Suppressions
+    ///
+    /// Provide a path to a YAML file of accepted Missing or Bad columns, or
+    /// glob patterns of them, with a reason and an optional expiry date.
+    /// Suppressed columns are not scored or raised in issues.
+    #[arg(long)]
+    suppressions: Option<String>,
+
+    /// Organisation rules
+    ///
+    /// Provide a path to a YAML file of organisation naming rules: allowed
+    /// custom namespaces, forbidden prefixes and patterns, with overrides per
+    /// dataset.
+    #[arg(long)]
+    rules: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Parse a naming rule from its id, offering the ids as possible values
+fn naming_rule_parser() -> impl TypedValueParser<Value = NamingRule> {
+    PossibleValuesParser::new(NamingRule::ALL.map(|rule| rule.id()))
+        .map(|id| NamingRule::from_id(&id).expect("possible values are rule ids"))
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Save the datasets and columns to a JSON snapshot file
+    ///
+    /// The snapshot can be used with `--from-snapshot` to run the reports
+    /// without calling the Honeycomb API. Use `--enums` to include the
+    /// variants of enum columns and `--scopes` to include the inferred
+    /// scopes. The model is still required: it decides which columns are
+    /// enums and which scopes are queried.
+    Snapshot {
+        /// Snapshot file path
+        #[arg(default_value_t = String::from("hh_snapshot.json"))]
+        path: String,
+    },
 }
 
 #[tokio::main]
@@ -552,8 +2470,8 @@ async fn main() -> anyhow::Result<()> {
     let mut root_dirs = vec![];
     for path in args.model {
         let p = Path::new(&path);
-        if !p.is_dir() {
-            anyhow::bail!("{} is not directory", path);
+        if !p.is_dir() && !p.is_file() {
+            anyhow::bail!("{} is not a directory or file", path);
         }
         root_dirs.push(
             p.canonicalize()?
@@ -562,31 +2480,168 @@ async fn main() -> anyhow::Result<()> {
                 .to_owned(),
         );
     }
-    let include_datasets = args.dataset.map(HashSet::from_iter);
-    let cm = ColumnUsageMap::new(&root_dirs, include_datasets, args.last_written_days).await?;
+    let include_datasets: Option<HashSet<String>> = args.dataset.map(HashSet::from_iter);
+    let mut sc = SemanticConventions::new(&root_dirs)?;
+    sc.namespace_limits = NamespaceLimits {
+        max_depth: args.max_namespace_depth,
+        max_extra_depth: args.max_extra_depth,
+    };
+    let org_rules = match &args.rules {
+        Some(path) => OrgRules::read(path)?,
+        None => OrgRules::default(),
+    };
+    sc.naming = NamingConfig {
+        disabled: args.disable_rule.iter().copied().collect(),
+        max_length: args.max_name_length,
+        ..Default::default()
+    };
+    org_rules.configure_naming(&mut sc.naming);
+    let source: Box<dyn ColumnSource> = match (&args.from_snapshot, &args.otlp) {
+        (Some(path), _) => Box::new(SnapshotSource::new(path)?),
+        (None, Some(paths)) => Box::new(OtlpSource::new(paths)?),
+        (None, None) => Box::new(
+            HoneycombSource::new(
+                include_datasets.clone(),
+                args.last_written_days,
+                args.enums
+                    || args.scopes
+                    || args.requirements
+                    || args.values
+                    || args.pii
+                    || args.cardinality,
+            )
+            .await?,
+        ),
+    };
+    let mut datasets = source.datasets().await?;
+    if let Some(include_datasets) = &include_datasets {
+        datasets.retain(|d| include_datasets.contains(&d.slug));
+    }
+    if args.scopes {
+        add_scopes(&sc, source.as_ref(), &mut datasets).await?;
+    }
+    if let Some(Command::Snapshot { path }) = &args.command {
+        let mut snapshot = Snapshot { datasets };
+        if args.enums {
+            snapshot.add_enums(&sc, source.as_ref()).await?;
+        }
+        snapshot.write(path)?;
+        println!(
+            "Snapshot of {} datasets written to {}",
+            snapshot.datasets.len(),
+            path
+        );
+        return Ok(());
+    }
+    let suppressions = match &args.suppressions {
+        Some(path) => Suppressions::read(path)?,
+        None => Suppressions::default(),
+    };
+    let cm = ColumnUsageMap::new(
+        sc,
+        source,
+        datasets,
+        args.unstable,
+        &org_rules,
+        &suppressions,
+    );
     if cm.datasets.is_empty() {
         println!("No datasets found");
         return Ok(());
     }
+    let mut enum_report_rows = vec![];
+    if args.enums {
+        enum_report_rows = cm.enum_report().await?;
+    }
     if cm.datasets.len() > 1 {
         cm.to_csv(&args.output)?;
+        if args.enums {
+            cm.write_enum_report(&enum_report_rows, &args.enum_output)?;
+        }
+    }
+    cm.print_health(args.enums.then_some(&enum_report_rows));
+    print_suppressions(&cm.suppression_counts(&suppressions));
+    print_expired_suppressions(&suppressions.expired());
+    if cm.datasets.len() > 1 {
+        cm.print_type_conflicts();
+        cm.print_families();
     }
-    cm.print_health();
     cm.print_dataset_report(args.show_matches);
-    let mut enum_report_rows = vec![];
     if args.enums {
-        enum_report_rows = cm.enum_report().await?;
         cm.print_enum_report(&enum_report_rows)?;
     }
+    if args.requirements {
+        let reports = cm.requirement_report().await?;
+        cm.print_requirement_report(&reports);
+    }
+    if args.values {
+        let reports = cm.value_report().await?;
+        cm.print_value_report(&reports);
+    }
+    if args.namespaces {
+        let tree = cm.namespace_tree();
+        cm.print_namespace_tree(&tree);
+        cm.write_namespace_tree(&tree, &args.namespace_output)?;
+    }
+    if args.coverage {
+        let root = args
+            .coverage_root
+            .as_deref()
+            .map(|root| -> anyhow::Result<String> {
+                Ok(Path::new(root)
+                    .canonicalize()?
+                    .to_str()
+                    .context("invalid path")?
+                    .to_owned())
+            })
+            .transpose()?;
+        let rows = cm.coverage_report(args.coverage_namespace.as_deref(), root.as_deref());
+        cm.print_coverage_report(&rows);
+    }
+    if args.synonyms {
+        cm.print_synonym_report(&cm.synonym_report());
+    }
+    if args.cardinality {
+        let reports = cm
+            .cardinality_report(args.max_cardinality, &args.low_cardinality)
+            .await?;
+        cm.print_cardinality_report(&reports, args.max_cardinality);
+    }
+    let mut pii_reports = vec![];
+    if args.pii {
+        pii_reports = cm.pii_report(args.max_pii_columns).await?;
+        cm.print_pii_report(&pii_reports);
+    }
     if let Some(repo) = args.github_issue {
         let (repo_owner, repo_name) = repo.split_once('/').context("Invalid repository")?;
         if let Some((header, body)) = cm.markdown_dataset_report() {
             octo::create_dataset_report_issue(repo_owner, repo_name, header, body).await?;
         }
-        if !enum_report_rows.is_empty() {
-            let (header, body) = cm.markdown_enum_report(enum_report_rows)?;
+        if let Some((header, body)) = cm.markdown_enum_report(enum_report_rows) {
             octo::create_enum_report_issue(repo_owner, repo_name, header, body).await?;
         }
+        if let Some((header, body)) = cm.markdown_pii_report(&pii_reports) {
+            octo::create_pii_report_issue(repo_owner, repo_name, header, body).await?;
+        }
+    }
+    let findings = cm.findings();
+    if let Some(path) = &args.write_baseline {
+        Baseline {
+            findings: findings.clone(),
+        }
+        .write(path)?;
+    }
+    if let Some(path) = &args.baseline {
+        let baseline = Baseline::read(path)?;
+        let comparison = baseline.compare(&findings);
+        cm.print_baseline_report(&comparison);
+        if !comparison.new.is_empty() {
+            anyhow::bail!(
+                "{} new findings since the baseline {}",
+                comparison.new.len(),
+                path
+            );
+        }
     }
     Ok(())
 }
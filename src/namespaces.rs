@@ -0,0 +1,98 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::semconv::Suggestion;
+
+/// The columns observed under a namespace, including those of its child
+/// namespaces
+#[derive(Debug, Default, Serialize)]
+pub struct NamespaceNode {
+    /// Whether the model defines the namespace
+    pub known: bool,
+    pub matching: usize,
+    pub missing: usize,
+    pub bad: usize,
+    pub datasets: BTreeSet<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub children: BTreeMap<String, NamespaceNode>,
+}
+
+impl NamespaceNode {
+    fn count(&mut self, suggestion: &Suggestion, datasets: &[&str]) {
+        match suggestion {
+            Suggestion::Matching => self.matching += 1,
+            Suggestion::Missing(_) => self.missing += 1,
+            Suggestion::Bad(_) => self.bad += 1,
+        }
+        self.datasets.extend(datasets.iter().map(|d| d.to_string()));
+    }
+}
+
+/// Top level namespaces, e.g. `http`, each holding its children, e.g.
+/// `http.request`
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct NamespaceTree {
+    pub roots: BTreeMap<String, NamespaceNode>,
+}
+
+impl NamespaceTree {
+    /// Roll a column up into every namespace of its name. Columns with no
+    /// namespace are not counted.
+    pub fn add(
+        &mut self,
+        column: &str,
+        suggestion: &Suggestion,
+        datasets: &[&str],
+        is_known: impl Fn(&str) -> bool,
+    ) {
+        let segments = column.split('.').collect::<Vec<_>>();
+        let mut nodes = &mut self.roots;
+        for depth in 1..segments.len() {
+            let namespace = segments[..depth].join(".");
+            let node = nodes.entry(namespace.clone()).or_default();
+            node.known = is_known(&namespace);
+            node.count(suggestion, datasets);
+            nodes = &mut node.children;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut tree = NamespaceTree::default();
+        let known = |ns: &str| ns.starts_with("http");
+        tree.add("http.request.method", &Suggestion::Matching, &["d1"], known);
+        tree.add(
+            "http.request.foo",
+            &Suggestion::Missing(vec![]),
+            &["d2"],
+            known,
+        );
+        tree.add("http.route", &Suggestion::Matching, &["d1"], known);
+        tree.add(
+            "app.checkout.total",
+            &Suggestion::Bad(vec![]),
+            &["d1"],
+            known,
+        );
+        tree.add("TaskId", &Suggestion::Bad(vec![]), &["d1"], known);
+
+        assert_eq!(tree.roots.len(), 2);
+        let http = &tree.roots["http"];
+        assert!(http.known);
+        assert_eq!((http.matching, http.missing, http.bad), (2, 1, 0));
+        assert_eq!(http.datasets.len(), 2);
+        let request = &http.children["http.request"];
+        assert_eq!((request.matching, request.missing), (1, 1));
+        assert!(request.children.is_empty());
+        let app = &tree.roots["app"];
+        assert!(!app.known);
+        assert_eq!(app.children["app.checkout"].bad, 1);
+    }
+}
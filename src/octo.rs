@@ -40,7 +40,8 @@ pub async fn create_enum_report_issue(
     markdown_header.push_str( 
         "This report was generated by [honey-health](https://github.com/jerbly/honey-health). \
                 The table shows enum columns found in the dataset with variants undefined in semantic conventions. \
-                This _could_ be an indication of data quality issues. Mistakes with casing or typos can lead to incorrect variants.\n\n\
+                This _could_ be an indication of data quality issues. Mistakes with casing or typos can lead to incorrect variants. \
+                Variants of enums which allow custom values are reported as `Info`, or `Warning` when they look like a typo of a defined variant.\n\n\
                 _Note: If the report is too large, it will be split into multiple comments._\n\n");
 
     create_table_issue(
@@ -53,6 +54,32 @@ pub async fn create_enum_report_issue(
     .await
 }
 
+pub async fn create_pii_report_issue(
+    repo_owner: &str,
+    repo_name: &str,
+    markdown_header: String,
+    markdown_rows: Vec<String>,
+) -> anyhow::Result<()> {
+    let issue_title = "Observability: Attribute values may contain personal data or secrets";
+    let mut markdown_header = markdown_header;
+    markdown_header.push_str(
+        "This report was generated by [honey-health](https://github.com/jerbly/honey-health). \
+                The table shows columns with values that look like personal data or secrets, \
+                such as emails, credit card numbers, tokens or keys. Samples are masked. \
+                Telemetry should not carry these values: remove or redact them in instrumentation.\n\n \
+                _Note: If the report is too large, it will be split into multiple comments._\n\n",
+    );
+
+    create_table_issue(
+        repo_owner,
+        repo_name,
+        markdown_header,
+        markdown_rows,
+        issue_title,
+    )
+    .await
+}
+
 /// Given a list of markdown rows, create a GitHub issue in the provided repository.
 /// The markdown rows will be split over multiple comments because of the maximum
 /// comment length limit of 65536 characters.
@@ -0,0 +1,308 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    semconv::AttributeScope,
+    snapshot::{Column, Dataset, VariantCounts},
+    source::ColumnSource,
+};
+
+// The maximum number of distinct values kept per column for the enum report
+const MAX_VARIANTS: usize = 1000;
+
+// A subset of the OTLP JSON encoding of `TracesData` as written, one per line,
+// by the OpenTelemetry Collector `file` exporter.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TracesData {
+    #[serde(default)]
+    resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Option<Resource>,
+    #[serde(default)]
+    scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resource {
+    #[serde(default)]
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScopeSpans {
+    #[serde(default)]
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Span {
+    #[serde(default)]
+    attributes: Vec<KeyValue>,
+    #[serde(default)]
+    events: Vec<Attributes>,
+    #[serde(default)]
+    links: Vec<Attributes>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attributes {
+    #[serde(default)]
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    key: String,
+    value: Option<AnyValue>,
+}
+
+// Only one of the values is set. Arrays, maps and bytes are not read as they
+// are all stored as strings in Honeycomb.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnyValue {
+    string_value: Option<String>,
+    // 64 bit integers are encoded as strings in OTLP JSON
+    int_value: Option<serde_json::Value>,
+    double_value: Option<f64>,
+    bool_value: Option<bool>,
+}
+
+impl AnyValue {
+    /// The Honeycomb column type for this value
+    fn column_type(&self) -> &'static str {
+        if self.int_value.is_some() {
+            "integer"
+        } else if self.double_value.is_some() {
+            "float"
+        } else if self.bool_value.is_some() {
+            "boolean"
+        } else {
+            "string"
+        }
+    }
+
+    fn variant(&self) -> Option<String> {
+        match (&self.string_value, &self.int_value, self.bool_value) {
+            (Some(s), _, _) => Some(s.clone()),
+            (_, Some(serde_json::Value::String(i)), _) => Some(i.clone()),
+            (_, Some(i), _) => Some(i.to_string()),
+            (_, _, Some(b)) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct OtlpColumn {
+    r#type: &'static str,
+    scopes: BTreeSet<AttributeScope>,
+    variants: VariantCounts,
+}
+
+/// Read OTLP JSON lines files written by the OpenTelemetry Collector `file`
+/// exporter. Each `service.name` becomes a dataset, as it would in Honeycomb,
+/// with the resource, span, span event and link attribute keys as columns.
+/// The scopes each column was set in are kept for the scope check.
+#[derive(Debug, Default)]
+pub struct OtlpSource {
+    datasets: BTreeMap<String, BTreeMap<String, OtlpColumn>>,
+}
+
+impl OtlpSource {
+    pub fn new(paths: &[String]) -> anyhow::Result<Self> {
+        let mut source = Self::default();
+        for path in paths {
+            let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+            for (line_num, line) in BufReader::new(file).lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let traces: TracesData = serde_json::from_str(&line)
+                    .with_context(|| format!("Invalid OTLP JSON at {}:{}", path, line_num + 1))?;
+                source.add_traces(traces);
+            }
+        }
+        Ok(source)
+    }
+
+    fn add_traces(&mut self, traces: TracesData) {
+        for resource_spans in traces.resource_spans {
+            let resource_attributes = resource_spans
+                .resource
+                .map(|r| r.attributes)
+                .unwrap_or_default();
+            let service_name = resource_attributes
+                .iter()
+                .find(|kv| kv.key == "service.name")
+                .and_then(|kv| kv.value.as_ref())
+                .and_then(|v| v.variant())
+                .unwrap_or_else(|| "unknown_service".to_owned());
+            let columns = self.datasets.entry(service_name).or_default();
+            Self::add_attributes(columns, AttributeScope::Resource, &resource_attributes);
+            for span in resource_spans.scope_spans.iter().flat_map(|s| &s.spans) {
+                Self::add_attributes(columns, AttributeScope::Span, &span.attributes);
+                for event in &span.events {
+                    Self::add_attributes(columns, AttributeScope::SpanEvent, &event.attributes);
+                }
+                for link in &span.links {
+                    Self::add_attributes(columns, AttributeScope::Link, &link.attributes);
+                }
+            }
+        }
+    }
+
+    fn add_attributes(
+        columns: &mut BTreeMap<String, OtlpColumn>,
+        scope: AttributeScope,
+        attributes: &[KeyValue],
+    ) {
+        for kv in attributes {
+            let Some(value) = &kv.value else {
+                continue;
+            };
+            // Like Honeycomb, the first type seen for a column is kept
+            let column = columns.entry(kv.key.clone()).or_insert_with(|| OtlpColumn {
+                r#type: value.column_type(),
+                scopes: BTreeSet::new(),
+                variants: VariantCounts::new(),
+            });
+            column.scopes.insert(scope);
+            if let Some(variant) = value.variant() {
+                if column.variants.len() < MAX_VARIANTS || column.variants.contains_key(&variant) {
+                    *column.variants.entry(variant).or_default() += 1;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ColumnSource for OtlpSource {
+    async fn datasets(&self) -> anyhow::Result<Vec<Dataset>> {
+        Ok(self
+            .datasets
+            .iter()
+            .map(|(slug, columns)| Dataset {
+                slug: slug.clone(),
+                columns: columns
+                    .iter()
+                    .map(|(key_name, column)| Column {
+                        key_name: key_name.clone(),
+                        r#type: column.r#type.to_owned(),
+                        last_written: None,
+                        description: None,
+                        scopes: column.scopes.iter().copied().collect(),
+                    })
+                    .collect(),
+                enums: None,
+            })
+            .collect())
+    }
+
+    async fn group_by_variants(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, VariantCounts)>> {
+        let columns = self
+            .datasets
+            .get(dataset_slug)
+            .with_context(|| format!("Unknown dataset {}", dataset_slug))?;
+        Ok(column_ids
+            .iter()
+            .filter_map(|c| {
+                columns
+                    .get(c)
+                    .map(|column| (c.clone(), column.variants.clone()))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_otlp_source() {
+        let line = r#"{"resourceSpans":[{"resource":{"attributes":[
+            {"key":"service.name","value":{"stringValue":"checkout"}}]},
+          "scopeSpans":[{"scope":{"name":"test"},"spans":[
+            {"traceId":"01","spanId":"02","name":"GET /cart","kind":2,
+             "attributes":[
+               {"key":"http.request.method","value":{"stringValue":"GET"}},
+               {"key":"http.response.status_code","value":{"intValue":"200"}},
+               {"key":"tags","value":{"arrayValue":{"values":[{"stringValue":"a"}]}}}],
+             "events":[{"name":"exception","attributes":[
+               {"key":"exception.escaped","value":{"boolValue":false}}]}],
+             "links":[{"traceId":"03","spanId":"04","attributes":[
+               {"key":"link.weight","value":{"doubleValue":0.5}}]}]},
+            {"traceId":"01","spanId":"05","name":"POST /cart",
+             "attributes":[
+               {"key":"http.request.method","value":{"stringValue":"POST"}},
+               {"key":"http.response.status_code","value":{"stringValue":"oops"}}]}]}]},
+          {"resource":{"attributes":[]},"scopeSpans":[{"spans":[{"attributes":[
+               {"key":"thing","value":{"stringValue":"x"}}]}]}]}]}"#;
+        let mut source = OtlpSource::default();
+        source.add_traces(serde_json::from_str(line).unwrap());
+
+        let datasets = source.datasets().await.unwrap();
+        assert_eq!(datasets.len(), 2);
+        assert_eq!(datasets[0].slug, "checkout");
+        assert_eq!(datasets[1].slug, "unknown_service");
+        let types = datasets[0]
+            .columns
+            .iter()
+            .map(|c| (c.key_name.as_str(), c.r#type.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                ("exception.escaped", "boolean"),
+                ("http.request.method", "string"),
+                ("http.response.status_code", "integer"),
+                ("link.weight", "float"),
+                ("service.name", "string"),
+                ("tags", "string"),
+            ]
+        );
+        let scopes = |key: &str| {
+            datasets[0]
+                .columns
+                .iter()
+                .find(|c| c.key_name == key)
+                .map(|c| c.scopes.clone())
+                .unwrap()
+        };
+        assert_eq!(scopes("service.name"), vec![AttributeScope::Resource]);
+        assert_eq!(scopes("http.request.method"), vec![AttributeScope::Span]);
+        assert_eq!(scopes("exception.escaped"), vec![AttributeScope::SpanEvent]);
+        assert_eq!(scopes("link.weight"), vec![AttributeScope::Link]);
+
+        let variants = source
+            .group_by_variants("checkout", &["http.request.method".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            variants,
+            vec![(
+                "http.request.method".to_string(),
+                VariantCounts::from([("GET".to_string(), 1), ("POST".to_string(), 1)])
+            )]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::HashMap,
+    env,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The API honeycomb-client reads the datasets and columns from
+const API_URL: &str = "https://api.honeycomb.io/1/";
+const MAX_RETRIES: usize = 5;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// The longest to wait for a query to complete
+const QUERY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A Honeycomb query specification. Only the parts used by the reports are
+/// included.
+#[derive(Debug, Default, Serialize)]
+pub struct QuerySpec {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub breakdowns: Vec<String>,
+    pub calculations: Vec<Calculation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<Filter>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub orders: Vec<Order>,
+    pub time_range: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Calculation {
+    pub op: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+}
+
+impl Calculation {
+    pub fn count() -> Self {
+        Self {
+            op: "COUNT".to_owned(),
+            column: None,
+        }
+    }
+
+    pub fn count_distinct(column: &str) -> Self {
+        Self {
+            op: "COUNT_DISTINCT".to_owned(),
+            column: Some(column.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Filter {
+    pub column: String,
+    pub op: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+impl Filter {
+    pub fn exists(column: &str) -> Self {
+        Self {
+            column: column.to_owned(),
+            op: "exists".to_owned(),
+            value: None,
+        }
+    }
+
+    pub fn equals(column: &str, value: &str) -> Self {
+        Self {
+            column: column.to_owned(),
+            op: "=".to_owned(),
+            value: Some(Value::String(value.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Order {
+    pub op: String,
+    pub order: String,
+}
+
+impl Order {
+    pub fn count_descending() -> Self {
+        Self {
+            op: "COUNT".to_owned(),
+            order: "descending".to_owned(),
+        }
+    }
+}
+
+/// A result row maps each breakdown column and calculation e.g. `COUNT` to
+/// its value
+pub type QueryRow = HashMap<String, Value>;
+
+#[derive(Debug, Deserialize)]
+struct Created {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResult {
+    id: String,
+    complete: bool,
+    data: Option<QueryResultData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResultData {
+    #[serde(default)]
+    results: Vec<QueryResultRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResultRow {
+    data: QueryRow,
+}
+
+/// Runs queries with the Honeycomb Query Data API, which honeycomb-client
+/// does not cover, with the same `HONEYCOMB_API_KEY` and API
+#[derive(Debug)]
+pub struct QueryClient {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl QueryClient {
+    /// Check with honeycomb-client that the API key can run queries
+    pub async fn new() -> anyhow::Result<Self> {
+        honeycomb_client::get_honeycomb(&["columns", "createDatasets", "queries"])
+            .await?
+            .context("API key does not have required access")?;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            api_key: env::var("HONEYCOMB_API_KEY").context("HONEYCOMB_API_KEY is not set")?,
+        })
+    }
+
+    /// Create the query, run it and wait for the results
+    pub async fn run(&self, dataset_slug: &str, spec: &QuerySpec) -> anyhow::Result<Vec<QueryRow>> {
+        let query: Created = self
+            .request(
+                reqwest::Method::POST,
+                &format!("queries/{}", dataset_slug),
+                Some(json!(spec)),
+            )
+            .await?;
+        let mut result: QueryResult = self
+            .request(
+                reqwest::Method::POST,
+                &format!("query_results/{}", dataset_slug),
+                Some(json!({ "query_id": query.id, "disable_series": true })),
+            )
+            .await?;
+        let started = Instant::now();
+        while !result.complete {
+            if started.elapsed() > QUERY_TIMEOUT {
+                anyhow::bail!(
+                    "Query {} in {} did not complete within {} seconds",
+                    query.id,
+                    dataset_slug,
+                    QUERY_TIMEOUT.as_secs()
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            result = self
+                .request(
+                    reqwest::Method::GET,
+                    &format!("query_results/{}/{}", dataset_slug, result.id),
+                    None,
+                )
+                .await?;
+        }
+        Ok(result
+            .data
+            .map(|d| d.results.into_iter().map(|r| r.data).collect())
+            .unwrap_or_default())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> anyhow::Result<T> {
+        let mut retries = 0;
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}{}", API_URL, path))
+                .header("X-Honeycomb-Team", &self.api_key);
+            if let Some(body) = &body {
+                request = request.json(body);
+            }
+            let response = request.send().await?;
+            // The query APIs are rate limited so back off and try again
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS && retries < MAX_RETRIES
+            {
+                retries += 1;
+                tokio::time::sleep(Duration::from_secs(10 * retries as u64)).await;
+                continue;
+            }
+            let response = response
+                .error_for_status()
+                .with_context(|| format!("Honeycomb request failed: {}", path))?;
+            return Ok(response.json().await?);
+        }
+    }
+}
+
+/// Read a query result value, e.g. a breakdown, as a string. Missing values
+/// are `None`.
+pub fn as_string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        v => Some(v.to_string()),
+    }
+}
+
+/// Read a query result value as a count
+pub fn as_count(value: Option<&Value>) -> u64 {
+    value.and_then(|v| v.as_f64()).unwrap_or(0.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_spec_json() {
+        let spec = QuerySpec {
+            breakdowns: vec!["meta.annotation_type".to_owned()],
+            calculations: vec![Calculation::count()],
+            filters: vec![Filter::exists("http.route")],
+            time_range: 3600,
+            ..Default::default()
+        };
+        assert_eq!(
+            json!(spec),
+            json!({
+                "breakdowns": ["meta.annotation_type"],
+                "calculations": [{"op": "COUNT"}],
+                "filters": [{"column": "http.route", "op": "exists"}],
+                "time_range": 3600
+            })
+        );
+    }
+
+    #[test]
+    fn test_count_distinct_json() {
+        let spec = QuerySpec {
+            calculations: vec![Calculation::count_distinct("http.route")],
+            time_range: 3600,
+            ..Default::default()
+        };
+        assert_eq!(
+            json!(spec),
+            json!({
+                "calculations": [{"op": "COUNT_DISTINCT", "column": "http.route"}],
+                "time_range": 3600
+            })
+        );
+    }
+}
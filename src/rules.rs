@@ -0,0 +1,347 @@
+use std::{collections::HashMap, fs::File, io::BufReader};
+
+use anyhow::Context;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::semconv::{NamingConfig, NamingRule, Severity, Suggestion, SuggestionComment};
+
+/// A rule set as written in the rules file. Fields given for a dataset
+/// replace those at the top level.
+#[derive(Debug, Default, Deserialize)]
+struct RuleSetFile {
+    allowed_namespaces: Option<Vec<String>>,
+    forbidden_prefixes: Option<Vec<String>>,
+    patterns: Option<Vec<PatternFile>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatternFile {
+    pattern: String,
+    message: String,
+    #[serde(default = "default_severity")]
+    severity: Severity,
+}
+
+fn default_severity() -> Severity {
+    Severity::Error
+}
+
+/// Naming rules to switch off and severities to change. These apply to
+/// every dataset.
+#[derive(Debug, Default, Deserialize)]
+struct NamingFile {
+    #[serde(default)]
+    disabled: Vec<NamingRule>,
+    #[serde(default)]
+    severity: HashMap<NamingRule, Severity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(flatten)]
+    default: RuleSetFile,
+    #[serde(default)]
+    naming: NamingFile,
+    #[serde(default)]
+    datasets: HashMap<String, RuleSetFile>,
+}
+
+/// A name pattern that is reported with its message
+#[derive(Debug)]
+struct Pattern {
+    regex: Regex,
+    message: String,
+    severity: Severity,
+}
+
+/// The organisation's rules for the custom attributes of a dataset
+#[derive(Debug, Default)]
+pub struct RuleSet {
+    allowed_namespaces: Vec<String>,
+    forbidden_prefixes: Vec<String>,
+    patterns: Vec<Pattern>,
+}
+
+impl RuleSet {
+    fn new(file: &RuleSetFile, default: Option<&RuleSetFile>) -> anyhow::Result<Self> {
+        let allowed_namespaces = file
+            .allowed_namespaces
+            .as_ref()
+            .or(default.and_then(|d| d.allowed_namespaces.as_ref()))
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|ns| ns.trim_end_matches('.').to_owned())
+            .collect();
+        let forbidden_prefixes = file
+            .forbidden_prefixes
+            .as_ref()
+            .or(default.and_then(|d| d.forbidden_prefixes.as_ref()))
+            .cloned()
+            .unwrap_or_default();
+        let patterns = file
+            .patterns
+            .as_ref()
+            .or(default.and_then(|d| d.patterns.as_ref()))
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .map(|p| {
+                        Ok(Pattern {
+                            regex: Regex::new(&p.pattern)
+                                .with_context(|| format!("Invalid pattern {}", p.pattern))?,
+                            message: p.message.clone(),
+                            severity: p.severity,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(RuleSet {
+            allowed_namespaces,
+            forbidden_prefixes,
+            patterns,
+        })
+    }
+
+    /// Check the name of a custom attribute, one not defined in the model
+    pub fn check(&self, name: &str) -> Vec<(SuggestionComment, Severity)> {
+        let mut findings = vec![];
+        if let Some((namespace, _)) = name.split_once('.') {
+            if !self.allowed_namespaces.is_empty()
+                && !self.allowed_namespaces.iter().any(|ns| ns == namespace)
+            {
+                findings.push((
+                    SuggestionComment::NamespaceNotAllowed(self.allowed_namespaces.clone()),
+                    Severity::Error,
+                ));
+            }
+        }
+        for prefix in &self.forbidden_prefixes {
+            if name.starts_with(prefix.as_str()) {
+                findings.push((
+                    SuggestionComment::ForbiddenPrefix(prefix.clone()),
+                    Severity::Error,
+                ));
+            }
+        }
+        for pattern in &self.patterns {
+            if pattern.regex.is_match(name) {
+                findings.push((
+                    SuggestionComment::Policy(pattern.message.clone()),
+                    pattern.severity,
+                ));
+            }
+        }
+        findings
+    }
+}
+
+/// Organisation naming rules read from a YAML file, e.g.
+///
+/// ```yaml
+/// allowed_namespaces: [acme, app]
+/// forbidden_prefixes: [debug., tmp.]
+/// patterns:
+///   - pattern: _ms$
+///     message: Record durations in seconds
+///     severity: warning
+/// naming:
+///   disabled: [array-plural]
+///   severity:
+///     no-namespace: warning
+/// datasets:
+///   legacy-billing:
+///     allowed_namespaces: [acme, app, billing]
+/// ```
+#[derive(Debug, Default)]
+pub struct OrgRules {
+    default: RuleSet,
+    datasets: HashMap<String, RuleSet>,
+    naming: NamingFile,
+}
+
+impl OrgRules {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        let rules: RulesFile = serde_yaml::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to read rules {}", path))?;
+        Self::from_file(rules).with_context(|| format!("Invalid rules in {}", path))
+    }
+
+    fn from_file(rules: RulesFile) -> anyhow::Result<Self> {
+        let datasets = rules
+            .datasets
+            .iter()
+            .map(|(slug, file)| Ok((slug.clone(), RuleSet::new(file, Some(&rules.default))?)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(OrgRules {
+            default: RuleSet::new(&rules.default, None)?,
+            datasets,
+            naming: rules.naming,
+        })
+    }
+
+    /// Switch off the naming rules and set the severities given in the file
+    pub fn configure_naming(&self, naming: &mut NamingConfig) {
+        naming.disabled.extend(self.naming.disabled.iter().copied());
+        naming
+            .severities
+            .extend(self.naming.severity.iter().map(|(r, s)| (*r, *s)));
+    }
+
+    /// The rules for a dataset, with its overrides
+    pub fn for_dataset(&self, slug: &str) -> &RuleSet {
+        self.datasets.get(slug).unwrap_or(&self.default)
+    }
+}
+
+/// Add findings to a suggestion, skipping comments it already has. Errors
+/// make it Bad.
+pub fn apply(suggestion: Suggestion, findings: &[(SuggestionComment, Severity)]) -> Suggestion {
+    let (mut comments, mut bad) = match suggestion {
+        Suggestion::Matching => return Suggestion::Matching,
+        Suggestion::Missing(comments) => (comments, false),
+        Suggestion::Bad(comments) => (comments, true),
+    };
+    for (comment, severity) in findings {
+        if !comments.contains(comment) {
+            comments.push(comment.clone());
+        }
+        bad |= *severity == Severity::Error;
+    }
+    if bad {
+        Suggestion::Bad(comments)
+    } else {
+        Suggestion::Missing(comments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(yaml: &str) -> OrgRules {
+        OrgRules::from_file(serde_yaml::from_str(yaml).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let rules = rules(
+            r#"
+allowed_namespaces: [acme., app]
+forbidden_prefixes: [debug., tmp.]
+patterns:
+  - pattern: _ms$
+    message: Record durations in seconds
+    severity: warning
+datasets:
+  legacy-billing:
+    allowed_namespaces: [acme, app, billing]
+"#,
+        );
+        let check = |slug: &str, name: &str| {
+            rules
+                .for_dataset(slug)
+                .check(name)
+                .into_iter()
+                .map(|(comment, severity)| (comment.to_string(), severity))
+                .collect::<Vec<_>>()
+        };
+        assert!(check("shop", "acme.cart.total").is_empty());
+        assert!(check("shop", "TaskId").is_empty());
+        assert_eq!(
+            check("shop", "billing.invoice.id"),
+            vec![(
+                "NamespaceNotAllowed use acme app".to_owned(),
+                Severity::Error
+            )]
+        );
+        assert_eq!(
+            check("shop", "debug.payload"),
+            vec![
+                (
+                    "NamespaceNotAllowed use acme app".to_owned(),
+                    Severity::Error
+                ),
+                ("ForbiddenPrefix debug.".to_owned(), Severity::Error),
+            ]
+        );
+        assert_eq!(
+            check("shop", "app.render_ms"),
+            vec![(
+                "Policy: Record durations in seconds".to_owned(),
+                Severity::Warning
+            )]
+        );
+        // The override replaces the allowed namespaces only
+        assert!(check("legacy-billing", "billing.invoice.id").is_empty());
+        assert_eq!(
+            check("legacy-billing", "tmp.x"),
+            vec![
+                (
+                    "NamespaceNotAllowed use acme app billing".to_owned(),
+                    Severity::Error
+                ),
+                ("ForbiddenPrefix tmp.".to_owned(), Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let warning = (SuggestionComment::Policy("p".to_owned()), Severity::Warning);
+        let error = (
+            SuggestionComment::ForbiddenPrefix("tmp.".to_owned()),
+            Severity::Error,
+        );
+        assert_eq!(
+            apply(Suggestion::Missing(vec![]), &[warning.clone()]),
+            Suggestion::Missing(vec![warning.0.clone()])
+        );
+        assert_eq!(
+            apply(
+                Suggestion::Missing(vec![warning.0.clone()]),
+                &[warning.clone(), error.clone()]
+            ),
+            Suggestion::Bad(vec![warning.0.clone(), error.0.clone()])
+        );
+        assert_eq!(apply(Suggestion::Matching, &[error]), Suggestion::Matching);
+    }
+
+    #[test]
+    fn test_configure_naming() {
+        let rules = rules(
+            r#"
+naming:
+  disabled: [array-plural]
+  severity:
+    no-namespace: warning
+    max-length: error
+"#,
+        );
+        let mut naming = NamingConfig::default();
+        naming.disabled.insert(NamingRule::WrongCase);
+        rules.configure_naming(&mut naming);
+        assert!(naming.disabled.contains(&NamingRule::WrongCase));
+        assert!(naming.disabled.contains(&NamingRule::ArrayPlural));
+        assert_eq!(naming.severity(NamingRule::NoNamespace), Severity::Warning);
+        assert_eq!(naming.severity(NamingRule::MaxLength), Severity::Error);
+        assert_eq!(naming.severity(NamingRule::EmptySegment), Severity::Error);
+    }
+
+    #[test]
+    fn test_unknown_naming_rule() {
+        let file = serde_yaml::from_str::<RulesFile>("naming: {disabled: [no-such-rule]}");
+        assert!(file.is_err());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let file: RulesFile =
+            serde_yaml::from_str("patterns: [{pattern: '(', message: m}]").unwrap();
+        assert!(OrgRules::from_file(file).is_err());
+    }
+}
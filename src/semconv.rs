@@ -1,10 +1,11 @@
+use anyhow::Context;
 use glob::glob;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{Display, Formatter},
     fs::File,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 use strsim::jaro;
 
@@ -39,6 +40,35 @@ impl Suggestion {
                 .join("; "),
         }
     }
+
+    /// Combine the suggestions for a column in several datasets. It is
+    /// matching only if it matches in all of them, otherwise it has the
+    /// comments of each and is bad if any is bad.
+    pub fn merge<'a>(suggestions: impl IntoIterator<Item = &'a Suggestion>) -> Suggestion {
+        let mut merged: Option<Vec<SuggestionComment>> = None;
+        let mut bad = false;
+        for suggestion in suggestions {
+            let comments = match suggestion {
+                Suggestion::Matching => continue,
+                Suggestion::Missing(comments) => comments,
+                Suggestion::Bad(comments) => {
+                    bad = true;
+                    comments
+                }
+            };
+            let merged = merged.get_or_insert_with(Vec::new);
+            for comment in comments {
+                if !merged.contains(comment) {
+                    merged.push(comment.clone());
+                }
+            }
+        }
+        match merged {
+            None => Suggestion::Matching,
+            Some(comments) if bad => Suggestion::Bad(comments),
+            Some(comments) => Suggestion::Missing(comments),
+        }
+    }
 }
 
 impl Display for Suggestion {
@@ -60,9 +90,33 @@ pub enum SuggestionComment {
     WrongCase,
     Similar(Vec<String>),
     Extends(String),
-    Deprecated(String),
-    //DeepNamespace, // TODO if the namespace is deep, it could indicate encoding a code path
+    Deprecated(Deprecated),
+    WrongType {
+        expected: String,
+        found: String,
+    },
+    WrongScope {
+        expected: Vec<AttributeScope>,
+        found: AttributeScope,
+    },
+    Unstable(String),
+    DeepNamespace {
+        depth: usize,
+        suggested: String,
+    },
     NoNamespace,
+    InvalidCharacter(Vec<char>),
+    EmptySegment,
+    NamespaceCollision,
+    ReservedPrefix(String),
+    WrongPlurality(String),
+    TooLong {
+        length: usize,
+        max: usize,
+    },
+    NamespaceNotAllowed(Vec<String>),
+    ForbiddenPrefix(String),
+    Policy(String),
 }
 
 impl SuggestionComment {
@@ -72,7 +126,44 @@ impl SuggestionComment {
             SuggestionComment::NoNamespace => "NoNamespace".to_string(),
             SuggestionComment::Similar(v) => format!("Similar to `{}`", v.join("`, `")),
             SuggestionComment::Extends(s) => format!("Extends `{}`", s),
-            SuggestionComment::Deprecated(s) => format!("Deprecated: {}", s),
+            SuggestionComment::Deprecated(d) => format!("Deprecated: {}", d.to_markdown()),
+            SuggestionComment::WrongType { expected, found } => {
+                format!("WrongType expected `{}` found `{}`", expected, found)
+            }
+            SuggestionComment::WrongScope { expected, found } => {
+                format!(
+                    "WrongScope expected `{}` found `{}`",
+                    join_scopes(expected),
+                    found
+                )
+            }
+            SuggestionComment::Unstable(s) => format!("Unstable: `{}`", s),
+            SuggestionComment::DeepNamespace { depth, suggested } => {
+                format!("DeepNamespace depth {} suggest `{}`", depth, suggested)
+            }
+            SuggestionComment::ReservedPrefix(s) => format!("ReservedPrefix `{}`", s),
+            SuggestionComment::WrongPlurality(s) => format!("WrongPlurality expected `{}`", s),
+            SuggestionComment::NamespaceNotAllowed(v) => {
+                format!("NamespaceNotAllowed use `{}`", v.join("`, `"))
+            }
+            SuggestionComment::ForbiddenPrefix(s) => format!("ForbiddenPrefix `{}`", s),
+            _ => self.to_string(),
+        }
+    }
+
+    /// The naming rule that made this comment, if any
+    pub fn rule(&self) -> Option<NamingRule> {
+        match self {
+            SuggestionComment::WrongCase => Some(NamingRule::WrongCase),
+            SuggestionComment::NoNamespace => Some(NamingRule::NoNamespace),
+            SuggestionComment::DeepNamespace { .. } => Some(NamingRule::DeepNamespace),
+            SuggestionComment::InvalidCharacter(_) => Some(NamingRule::InvalidCharacter),
+            SuggestionComment::EmptySegment => Some(NamingRule::EmptySegment),
+            SuggestionComment::NamespaceCollision => Some(NamingRule::NamespaceCollision),
+            SuggestionComment::ReservedPrefix(_) => Some(NamingRule::ReservedPrefix),
+            SuggestionComment::WrongPlurality(_) => Some(NamingRule::ArrayPlural),
+            SuggestionComment::TooLong { .. } => Some(NamingRule::MaxLength),
+            _ => None,
         }
     }
 }
@@ -84,7 +175,125 @@ impl Display for SuggestionComment {
             SuggestionComment::NoNamespace => write!(f, "NoNamespace"),
             SuggestionComment::Similar(v) => write!(f, "Similar to {}", v.join(" ")),
             SuggestionComment::Extends(s) => write!(f, "Extends {}", s),
-            SuggestionComment::Deprecated(s) => write!(f, "Deprecated: {}", s),
+            SuggestionComment::Deprecated(d) => write!(f, "Deprecated: {}", d),
+            SuggestionComment::WrongType { expected, found } => {
+                write!(f, "WrongType expected {} found {}", expected, found)
+            }
+            SuggestionComment::WrongScope { expected, found } => {
+                write!(
+                    f,
+                    "WrongScope expected {} found {}",
+                    join_scopes(expected),
+                    found
+                )
+            }
+            SuggestionComment::Unstable(s) => write!(f, "Unstable: {}", s),
+            SuggestionComment::DeepNamespace { depth, suggested } => {
+                write!(f, "DeepNamespace depth {} suggest {}", depth, suggested)
+            }
+            SuggestionComment::InvalidCharacter(chars) => {
+                write!(f, "InvalidCharacter")?;
+                for c in chars {
+                    write!(f, " {:?}", c)?;
+                }
+                Ok(())
+            }
+            SuggestionComment::EmptySegment => write!(f, "EmptySegment"),
+            SuggestionComment::NamespaceCollision => write!(f, "NamespaceCollision"),
+            SuggestionComment::ReservedPrefix(s) => write!(f, "ReservedPrefix {}", s),
+            SuggestionComment::WrongPlurality(s) => write!(f, "WrongPlurality expected {}", s),
+            SuggestionComment::TooLong { length, max } => {
+                write!(f, "TooLong length {} max {}", length, max)
+            }
+            SuggestionComment::NamespaceNotAllowed(v) => {
+                write!(f, "NamespaceNotAllowed use {}", v.join(" "))
+            }
+            SuggestionComment::ForbiddenPrefix(s) => write!(f, "ForbiddenPrefix {}", s),
+            SuggestionComment::Policy(s) => write!(f, "Policy: {}", s),
+        }
+    }
+}
+
+/// Where an attribute was set. Honeycomb flattens all of these into the
+/// same columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeScope {
+    Resource,
+    Span,
+    SpanEvent,
+    Link,
+}
+
+impl AttributeScope {
+    /// The scope of the attributes in a group of this type. Links have no
+    /// group type so are never expected.
+    fn from_group_type(group_type: &str) -> Option<Self> {
+        match group_type {
+            "resource" | "entity" => Some(AttributeScope::Resource),
+            "span" => Some(AttributeScope::Span),
+            "event" => Some(AttributeScope::SpanEvent),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AttributeScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeScope::Resource => write!(f, "resource"),
+            AttributeScope::Span => write!(f, "span"),
+            AttributeScope::SpanEvent => write!(f, "span_event"),
+            AttributeScope::Link => write!(f, "link"),
+        }
+    }
+}
+
+fn join_scopes(scopes: &[AttributeScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Deprecation is a plain note in the legacy schema and a structured object
+/// with a reason, and possibly a rename target, in the current schema.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Deprecated {
+    Note(String),
+    Structured {
+        reason: String,
+        renamed_to: Option<String>,
+        note: Option<String>,
+    },
+}
+
+impl Deprecated {
+    pub fn to_markdown(&self) -> String {
+        match self {
+            Deprecated::Structured {
+                renamed_to: Some(renamed_to),
+                ..
+            } => format!("Renamed to `{}`", renamed_to),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl Display for Deprecated {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Deprecated::Note(s) => write!(f, "{}", s.trim()),
+            Deprecated::Structured {
+                renamed_to: Some(renamed_to),
+                ..
+            } => write!(f, "Renamed to {}", renamed_to),
+            Deprecated::Structured {
+                note: Some(note), ..
+            } => write!(f, "{}", note.trim()),
+            Deprecated::Structured { reason, .. } => write!(f, "{}", reason),
         }
     }
 }
@@ -107,21 +316,70 @@ impl Display for MemberValue {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Member {
+    #[serde(default)]
+    pub id: String,
     pub value: MemberValue,
+    pub brief: Option<String>,
+    pub stability: Option<String>,
+}
+
+impl Member {
+    /// Whether a variant found in a dataset is this member. Integer members
+    /// are compared numerically, string members must match exactly.
+    fn defines(&self, variant: &str) -> bool {
+        match &self.value {
+            MemberValue::StringType(s) => s.trim() == variant,
+            MemberValue::IntegerType(i) => {
+                variant.trim().parse::<f64>().is_ok_and(|v| v == *i as f64)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ComplexType {
+    /// Open enums allow values other than the members
+    #[serde(default)]
+    pub allow_custom_values: bool,
     #[serde(default)]
     pub members: Vec<Member>,
 }
 
 impl ComplexType {
-    pub fn get_simple_variants(&self) -> Vec<String> {
-        self.members
+    /// Whether a variant found in a dataset is one of the members
+    pub fn is_defined(&self, variant: &str) -> bool {
+        self.members.iter().any(|member| member.defines(variant))
+    }
+
+    /// The defined member an undefined variant was most likely meant to be.
+    /// The variant is trimmed and compared ignoring case before falling back
+    /// to string similarity. Deprecated members are never suggested.
+    pub fn closest_variant(&self, variant: &str) -> Option<&Member> {
+        let candidates = self
+            .members
             .iter()
-            .map(|member| member.value.to_string().trim().to_string())
-            .collect()
+            .filter(|member| member.stability.as_deref() != Some("deprecated"))
+            .filter_map(|member| match &member.value {
+                MemberValue::StringType(s) => Some((s.trim(), member)),
+                MemberValue::IntegerType(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let trimmed = variant.trim();
+        if let Some((_, member)) = candidates.iter().find(|(v, _)| *v == trimmed) {
+            return Some(member);
+        }
+        if let Some((_, member)) = candidates
+            .iter()
+            .find(|(v, _)| v.eq_ignore_ascii_case(trimmed))
+        {
+            return Some(member);
+        }
+        candidates
+            .into_iter()
+            .map(|(v, member)| (jaro(&v.to_lowercase(), &trimmed.to_lowercase()), member))
+            .filter(|(similarity, _)| *similarity > 0.85)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, member)| member)
     }
 }
 
@@ -132,14 +390,105 @@ pub enum Type {
     Complex(ComplexType),
 }
 
-#[derive(Debug, Deserialize)]
+impl Type {
+    /// The Honeycomb column types that can hold values of this type. The first
+    /// is the preferred type. Arrays are stored as JSON strings.
+    fn column_types(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Type::Simple(s) => {
+                let s = s
+                    .strip_prefix("template[")
+                    .and_then(|s| s.strip_suffix(']'))
+                    .unwrap_or(s);
+                match s {
+                    "string" => Some(&["string"]),
+                    "int" => Some(&["integer"]),
+                    "double" => Some(&["float", "integer"]),
+                    "boolean" => Some(&["boolean"]),
+                    s if s.ends_with("[]") => Some(&["string"]),
+                    _ => None,
+                }
+            }
+            Type::Complex(c) => {
+                if !c.members.is_empty()
+                    && c.members
+                        .iter()
+                        .all(|m| matches!(m.value, MemberValue::IntegerType(_)))
+                {
+                    Some(&["integer"])
+                } else {
+                    Some(&["string"])
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RequirementLevel {
+    Simple(String),
+    Detailed(HashMap<String, String>),
+}
+
+impl RequirementLevel {
+    /// The level without the condition or note e.g. `conditionally_required`
+    pub fn level(&self) -> &str {
+        match self {
+            RequirementLevel::Simple(s) => s,
+            RequirementLevel::Detailed(m) => m.keys().next().map_or("", |k| k.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct Attribute {
+    // Resolved registries give the fully qualified `name` instead
+    #[serde(alias = "name")]
     pub id: Option<String>,
+    pub r#ref: Option<String>,
     pub r#type: Option<Type>,
-    pub deprecated: Option<String>,
+    pub brief: Option<String>,
+    pub requirement_level: Option<RequirementLevel>,
+    pub stability: Option<String>,
+    pub deprecated: Option<Deprecated>,
+    pub annotations: Option<Annotations>,
+}
+
+/// Extension keys on an attribute. Only the `honey_health` key is read.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Annotations {
+    pub honey_health: Option<Constraints>,
+}
+
+/// Optional constraints on the values of an attribute, checked by the value
+/// report e.g.
+///
+/// ```yaml
+/// annotations:
+///   honey_health:
+///     min: 0
+///     max: 10
+///     pattern: "^[a-z]+$"
+/// ```
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Constraints {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub pattern: Option<String>,
 }
 
 impl Attribute {
+    /// The deprecation of this attribute, if any. Older models may only mark
+    /// the stability as deprecated.
+    pub fn deprecation(&self) -> Option<Deprecated> {
+        match (&self.deprecated, self.stability.as_deref()) {
+            (Some(deprecated), _) => Some(deprecated.clone()),
+            (None, Some("deprecated")) => Some(Deprecated::Note("Stability is deprecated".into())),
+            _ => None,
+        }
+    }
+
     fn is_template(&self) -> bool {
         if let Some(Type::Simple(r#type)) = &self.r#type {
             r#type.starts_with("template")
@@ -151,7 +500,11 @@ impl Attribute {
 
 #[derive(Debug, Deserialize)]
 struct Group {
+    id: Option<String>,
+    r#type: Option<String>,
     prefix: Option<String>,
+    extends: Option<String>,
+    span_kind: Option<String>,
     attributes: Option<Vec<Attribute>>,
 }
 
@@ -160,29 +513,352 @@ struct Groups {
     groups: Vec<Group>,
 }
 
+/// The output of `weaver registry resolve`. Group attributes are either
+/// indexes into the attribute catalog or the attributes themselves, and the
+/// groups may sit under `registry` or at the top level.
+#[derive(Debug, Deserialize)]
+struct ResolvedSchema {
+    catalog: Option<ResolvedCatalog>,
+    registry: Option<ResolvedRegistry>,
+    groups: Option<Vec<ResolvedGroup>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolvedCatalog {
+    attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolvedRegistry {
+    groups: Vec<ResolvedGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolvedGroup {
+    id: String,
+    r#type: Option<String>,
+    span_kind: Option<String>,
+    #[serde(default)]
+    attributes: Vec<ResolvedAttribute>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ResolvedAttribute {
+    Index(usize),
+    Attribute(Box<Attribute>),
+}
+
+impl ResolvedSchema {
+    fn into_groups(self) -> anyhow::Result<Groups> {
+        let catalog = self.catalog.map(|c| c.attributes).unwrap_or_default();
+        let resolved_groups = match (self.registry, self.groups) {
+            (Some(registry), _) => registry.groups,
+            (None, Some(groups)) => groups,
+            (None, None) => anyhow::bail!("No groups found"),
+        };
+        let mut groups = vec![];
+        for group in resolved_groups {
+            let mut attributes = vec![];
+            for attribute in group.attributes {
+                attributes.push(match attribute {
+                    ResolvedAttribute::Attribute(attribute) => *attribute,
+                    ResolvedAttribute::Index(i) => catalog.get(i).cloned().with_context(|| {
+                        format!(
+                            "group `{}` refers to missing catalog attribute {}",
+                            group.id, i
+                        )
+                    })?,
+                });
+            }
+            groups.push(Group {
+                id: Some(group.id),
+                r#type: group.r#type,
+                prefix: None,
+                extends: None,
+                span_kind: group.span_kind,
+                attributes: Some(attributes),
+            });
+        }
+        Ok(Groups { groups })
+    }
+}
+
+/// A group as read from a model file, kept until all files are loaded so that
+/// `ref` and `extends` can be resolved across files.
 #[derive(Debug)]
+struct LoadedGroup {
+    path: PathBuf,
+    group: Group,
+}
+
+/// A group with its `ref` and `extends` attributes resolved. The attributes
+/// are keyed by their fully qualified name.
+#[derive(Debug, Clone)]
+pub struct SemanticGroup {
+    pub r#type: Option<String>,
+    pub span_kind: Option<String>,
+    pub attributes: BTreeMap<String, Attribute>,
+}
+
+impl SemanticGroup {
+    /// The attributes with their requirement level, most required first.
+    /// `opt_in` and template attributes are not included. Attributes without
+    /// a level are `recommended`.
+    pub fn requirement_levels(&self) -> Vec<(&str, &str)> {
+        let mut levels = self
+            .attributes
+            .iter()
+            .filter(|(_, attribute)| !attribute.is_template())
+            .map(|(name, attribute)| {
+                let level = attribute
+                    .requirement_level
+                    .as_ref()
+                    .map_or("recommended", |r| r.level());
+                (name.as_str(), level)
+            })
+            .filter(|(_, level)| *level != "opt_in")
+            .collect::<Vec<_>>();
+        levels.sort_by_key(|(name, level)| {
+            let rank = match *level {
+                "required" => 0,
+                "conditionally_required" => 1,
+                _ => 2,
+            };
+            (rank, *name)
+        });
+        levels
+    }
+}
+
+/// The attribute naming rules, from the OpenTelemetry naming spec, applied
+/// to columns without a semantic convention. The kebab case names, e.g.
+/// `wrong-case`, are stable ids used to switch rules off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NamingRule {
+    /// Upper case letters
+    WrongCase,
+    /// No `.` separating a namespace from the name
+    NoNamespace,
+    /// Too many namespace segments, or a code path
+    DeepNamespace,
+    /// Characters other than lower case letters, digits, `_` and `.`
+    InvalidCharacter,
+    /// Leading, trailing or repeated dots e.g. `a..b`
+    EmptySegment,
+    /// The name is also a namespace in the model e.g. `http.request`
+    NamespaceCollision,
+    /// The `otel.` prefix is reserved for the OpenTelemetry specification
+    ReservedPrefix,
+    /// Array attributes are plural, others singular
+    ArrayPlural,
+    /// Longer than the maximum name length
+    MaxLength,
+}
+
+/// How serious breaking a naming rule is. Errors make a column Bad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl NamingRule {
+    pub const ALL: [NamingRule; 9] = [
+        NamingRule::WrongCase,
+        NamingRule::NoNamespace,
+        NamingRule::DeepNamespace,
+        NamingRule::InvalidCharacter,
+        NamingRule::EmptySegment,
+        NamingRule::NamespaceCollision,
+        NamingRule::ReservedPrefix,
+        NamingRule::ArrayPlural,
+        NamingRule::MaxLength,
+    ];
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            NamingRule::WrongCase => "wrong-case",
+            NamingRule::NoNamespace => "no-namespace",
+            NamingRule::DeepNamespace => "deep-namespace",
+            NamingRule::InvalidCharacter => "invalid-character",
+            NamingRule::EmptySegment => "empty-segment",
+            NamingRule::NamespaceCollision => "namespace-collision",
+            NamingRule::ReservedPrefix => "reserved-prefix",
+            NamingRule::ArrayPlural => "array-plural",
+            NamingRule::MaxLength => "max-length",
+        }
+    }
+
+    /// The severity unless the rules file sets another
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            NamingRule::NamespaceCollision | NamingRule::ArrayPlural | NamingRule::MaxLength => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// Which naming rules are checked, and how serious they are
+#[derive(Debug, Clone)]
+pub struct NamingConfig {
+    pub disabled: HashSet<NamingRule>,
+    pub severities: HashMap<NamingRule, Severity>,
+    pub max_length: usize,
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        NamingConfig {
+            disabled: HashSet::new(),
+            severities: HashMap::new(),
+            max_length: 255,
+        }
+    }
+}
+
+impl NamingConfig {
+    pub fn severity(&self, rule: NamingRule) -> Severity {
+        self.severities
+            .get(&rule)
+            .copied()
+            .unwrap_or(rule.default_severity())
+    }
+}
+
+/// How deep a column's namespace can be before it is reported as a
+/// `DeepNamespace`
+#[derive(Debug, Clone, Copy)]
+pub struct NamespaceLimits {
+    /// The most segments before the final one, e.g. 2 for `http.request.method`
+    pub max_depth: usize,
+    /// The most segments allowed beyond the nearest namespace in the model
+    pub max_extra_depth: usize,
+}
+
+impl Default for NamespaceLimits {
+    fn default() -> Self {
+        NamespaceLimits {
+            max_depth: 5,
+            max_extra_depth: 3,
+        }
+    }
+}
+
+/// Top level domains that start a reverse domain name, e.g. `com.acme`.
+/// `net` and `io` are left out as `net.*` is an OpenTelemetry namespace.
+const REVERSE_DOMAINS: &[&str] = &["com", "org", "dev"];
+
+/// Whether the name starts with a reverse domain name, e.g. `com.acme`
+fn is_reverse_domain(segments: &[&str]) -> bool {
+    segments.len() >= 4 && REVERSE_DOMAINS.contains(&segments[0])
+}
+
+/// Whether a segment is named like a class, e.g. `OrderController`
+fn is_pascal_case(segment: &str) -> bool {
+    segment.starts_with(|c: char| c.is_ascii_uppercase())
+        && segment.chars().any(|c| c.is_ascii_lowercase())
+}
+
+/// Whether the name looks like a code path, e.g. a package name
+/// `com.acme.service.order.duration` or a class and method
+/// `OrderController.create.duration`
+fn is_code_path(segments: &[&str]) -> bool {
+    let reverse_domain = is_reverse_domain(segments);
+    // A class inside a namespace followed by a method, before the final
+    // segment
+    let class_method = segments.len() >= 4
+        && segments[1..segments.len() - 2]
+            .iter()
+            .any(|segment| is_pascal_case(segment));
+    reverse_domain || class_method
+}
+
+/// Suffixes dropped from a class name when it is collapsed with its method
+const CLASS_SUFFIXES: &[&str] = &["Controller", "Service", "Handler", "Manager", "Impl"];
+
+/// Snake case the segments, collapsing each class and the method after it
+/// into one segment, e.g. `OrderController.create` becomes `order_create`.
+/// The last segment is never a method.
+fn collapse_code_path(segments: &[&str]) -> Vec<String> {
+    let mut collapsed = vec![];
+    let mut i = 0;
+    while i < segments.len() {
+        let segment = segments[i];
+        if is_pascal_case(segment) && i + 2 < segments.len() {
+            let class = CLASS_SUFFIXES
+                .iter()
+                .find_map(|suffix| segment.strip_suffix(suffix))
+                .filter(|class| !class.is_empty())
+                .unwrap_or(segment);
+            collapsed.push(format!(
+                "{}_{}",
+                to_snake_case(class),
+                to_snake_case(segments[i + 1])
+            ));
+            i += 2;
+        } else {
+            collapsed.push(to_snake_case(segment));
+            i += 1;
+        }
+    }
+    collapsed
+}
+
+/// `OrderController` becomes `order_controller`
+fn to_snake_case(segment: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in segment.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+#[derive(Debug, Default)]
 pub struct SemanticConventions {
     // Have a map of constructed-attribute-name as key, to, attribute as value
     pub attribute_map: HashMap<String, Option<Attribute>>,
     pub prefixes: HashSet<String>,
     pub templates: HashMap<String, Option<Attribute>>,
+    // Attribute or template name to the model root it was defined in
+    pub attribute_roots: HashMap<String, String>,
+    // Group id to the resolved group
+    pub groups: HashMap<String, SemanticGroup>,
+    pub namespace_limits: NamespaceLimits,
+    pub naming: NamingConfig,
 }
 
 impl SemanticConventions {
     pub fn new(root_dirs: &[String]) -> anyhow::Result<Self> {
-        let mut sc = SemanticConventions {
-            attribute_map: HashMap::new(),
-            prefixes: HashSet::new(),
-            templates: HashMap::new(),
-        };
+        let mut sc = SemanticConventions::default();
         sc.populate_builtins();
+        let mut loaded = vec![];
         for root_dir in root_dirs {
-            let yml = format!("{root_dir}/**/*.yml");
-            let yaml = format!("{root_dir}/**/*.yaml");
-            for entry in glob(yml.as_str())?.chain(glob(yaml.as_str())?) {
-                sc.read_file(entry?)?;
+            if Path::new(root_dir).is_file() {
+                loaded.extend(sc.read_resolved_file(PathBuf::from(root_dir))?);
+            } else {
+                let yml = format!("{root_dir}/**/*.yml");
+                let yaml = format!("{root_dir}/**/*.yaml");
+                for entry in glob(yml.as_str())?.chain(glob(yaml.as_str())?) {
+                    loaded.extend(sc.read_file(entry?)?);
+                }
             }
+            sc.record_root(root_dir);
         }
+        sc.resolve_groups(&loaded)?;
         Ok(sc)
     }
 
@@ -216,33 +892,206 @@ impl SemanticConventions {
         }
     }
 
-    pub fn read_file(&mut self, path: PathBuf) -> anyhow::Result<()> {
+    /// Mark the attributes and templates not yet seen in an earlier root as
+    /// defined in this one. Builtins have no root.
+    fn record_root(&mut self, root_dir: &str) {
+        let names = self
+            .attribute_map
+            .iter()
+            .chain(&self.templates)
+            .filter(|(_, attribute)| attribute.is_some())
+            .map(|(name, _)| name);
+        for name in names {
+            self.attribute_roots
+                .entry(name.clone())
+                .or_insert_with(|| root_dir.to_owned());
+        }
+    }
+
+    fn read_file(&mut self, path: PathBuf) -> anyhow::Result<Vec<LoadedGroup>> {
         //println!("{:?}", path.as_os_str());
-        let groups: Groups = serde_yaml::from_reader(&File::open(path)?)?;
-        for group in groups.groups {
-            if let Some(attributes) = group.attributes {
+        let groups: Groups = serde_yaml::from_reader(&File::open(&path)?)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(self.register_groups(path, groups))
+    }
+
+    /// Read a registry produced by `weaver registry resolve`, in JSON or YAML.
+    /// Refs are already expanded so every group attribute carries its name.
+    fn read_resolved_file(&mut self, path: PathBuf) -> anyhow::Result<Vec<LoadedGroup>> {
+        let resolved: ResolvedSchema = serde_yaml::from_reader(&File::open(&path)?)
+            .with_context(|| format!("Failed to read resolved registry {}", path.display()))?;
+        let groups = resolved
+            .into_groups()
+            .with_context(|| format!("Invalid resolved registry {}", path.display()))?;
+        Ok(self.register_groups(path, groups))
+    }
+
+    /// Register every attribute defined with an `id` and return the groups so
+    /// that they can be resolved once all files have been read.
+    fn register_groups(&mut self, path: PathBuf, groups: Groups) -> Vec<LoadedGroup> {
+        for group in &groups.groups {
+            if let Some(attributes) = &group.attributes {
                 for attribute in attributes {
-                    let is_template = attribute.is_template();
                     if let Some(id) = &attribute.id {
-                        let attribute_name = match &group.prefix {
-                            Some(prefix) => {
-                                format!("{}.{}", prefix, id)
-                            }
-                            None => id.to_string(),
-                        };
+                        let attribute_name = Self::attribute_name(group.prefix.as_deref(), id);
                         self.insert_prefixes(&attribute_name);
-                        if is_template {
-                            self.templates.insert(attribute_name, Some(attribute));
+                        if attribute.is_template() {
+                            self.templates
+                                .insert(attribute_name, Some(attribute.clone()));
                         } else {
-                            self.attribute_map.insert(attribute_name, Some(attribute));
+                            self.attribute_map
+                                .insert(attribute_name, Some(attribute.clone()));
                         }
                     }
                 }
             }
         }
+        groups
+            .groups
+            .into_iter()
+            .map(|group| LoadedGroup {
+                path: path.clone(),
+                group,
+            })
+            .collect()
+    }
+
+    fn attribute_name(prefix: Option<&str>, id: &str) -> String {
+        match prefix {
+            Some(prefix) if !prefix.is_empty() => format!("{}.{}", prefix, id),
+            _ => id.to_string(),
+        }
+    }
+
+    /// Build the per-group view, following `ref` and `extends` across all the
+    /// loaded files. Any unresolved references are reported together.
+    fn resolve_groups(&mut self, loaded: &[LoadedGroup]) -> anyhow::Result<()> {
+        let mut by_id: HashMap<&str, &LoadedGroup> = HashMap::new();
+        let mut errors = vec![];
+        for lg in loaded {
+            if let Some(id) = lg.group.id.as_deref() {
+                if let Some(first) = by_id.get(id) {
+                    errors.push(format!(
+                        "{}: group `{}` is already defined in {}",
+                        lg.path.display(),
+                        id,
+                        first.path.display()
+                    ));
+                } else {
+                    by_id.insert(id, lg);
+                }
+            }
+        }
+        let mut ids = by_id.keys().copied().collect::<Vec<_>>();
+        ids.sort();
+        for id in ids {
+            self.resolve_group(id, &by_id, &mut HashSet::new(), &mut errors);
+        }
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Failed to resolve semantic conventions:\n  {}",
+                errors.join("\n  ")
+            );
+        }
         Ok(())
     }
 
+    fn resolve_group<'a>(
+        &mut self,
+        id: &'a str,
+        by_id: &HashMap<&'a str, &'a LoadedGroup>,
+        visiting: &mut HashSet<&'a str>,
+        errors: &mut Vec<String>,
+    ) {
+        if self.groups.contains_key(id) {
+            return;
+        }
+        let lg = by_id[id];
+        if !visiting.insert(id) {
+            errors.push(format!(
+                "{}: group `{}` has a cyclic `extends`",
+                lg.path.display(),
+                id
+            ));
+            return;
+        }
+
+        let mut attributes = BTreeMap::new();
+        if let Some(parent) = &lg.group.extends {
+            if let Some((parent, _)) = by_id.get_key_value(parent.as_str()) {
+                self.resolve_group(parent, by_id, visiting, errors);
+                if let Some(parent_group) = self.groups.get(*parent) {
+                    attributes.extend(parent_group.attributes.clone());
+                }
+            } else {
+                errors.push(format!(
+                    "{}: group `{}` extends unknown group `{}`",
+                    lg.path.display(),
+                    id,
+                    parent
+                ));
+            }
+        }
+        for attribute in lg.group.attributes.iter().flatten() {
+            match self.resolve_attribute(lg.group.prefix.as_deref(), attribute) {
+                Ok(Some((name, resolved))) => {
+                    attributes.insert(name, resolved);
+                }
+                Ok(None) => {}
+                Err(r#ref) => errors.push(format!(
+                    "{}: group `{}` has unresolved ref `{}`",
+                    lg.path.display(),
+                    id,
+                    r#ref
+                )),
+            }
+        }
+        visiting.remove(id);
+        self.groups.insert(
+            id.to_owned(),
+            SemanticGroup {
+                r#type: lg.group.r#type.clone(),
+                span_kind: lg.group.span_kind.clone(),
+                attributes,
+            },
+        );
+    }
+
+    /// Resolve an attribute to its full name and definition. A `ref` takes the
+    /// registry definition with any brief or requirement level overridden.
+    /// Returns the ref as the error if it cannot be found.
+    fn resolve_attribute(
+        &self,
+        prefix: Option<&str>,
+        attribute: &Attribute,
+    ) -> Result<Option<(String, Attribute)>, String> {
+        if let Some(id) = &attribute.id {
+            return Ok(Some((Self::attribute_name(prefix, id), attribute.clone())));
+        }
+        let Some(r#ref) = &attribute.r#ref else {
+            return Ok(None);
+        };
+        let base = self
+            .attribute_map
+            .get(r#ref)
+            .or_else(|| self.templates.get(r#ref))
+            .ok_or_else(|| r#ref.clone())?;
+        let mut resolved = match base {
+            Some(base) => base.clone(),
+            None => Attribute {
+                id: Some(r#ref.clone()),
+                ..Default::default()
+            },
+        };
+        if attribute.brief.is_some() {
+            resolved.brief = attribute.brief.clone();
+        }
+        if attribute.requirement_level.is_some() {
+            resolved.requirement_level = attribute.requirement_level.clone();
+        }
+        Ok(Some((r#ref.clone(), resolved)))
+    }
+
     fn contains_uppercase(input: &str) -> bool {
         for c in input.chars() {
             if c.is_ascii_uppercase() {
@@ -281,6 +1130,109 @@ impl SemanticConventions {
         input.contains('.')
     }
 
+    /// The singular or plural name of a model attribute, when the name only
+    /// differs by a trailing `s` and the attribute's array type calls for it
+    fn check_plurality(&self, name: &str) -> Option<SuggestionComment> {
+        let is_array = |n: &str| {
+            self.get_value_type(n)
+                .is_some_and(|(t, _)| t.ends_with("[]"))
+        };
+        let plural = format!("{}s", name);
+        if is_array(&plural) {
+            return Some(SuggestionComment::WrongPlurality(plural));
+        }
+        let singular = name.strip_suffix('s')?;
+        (self.find_attribute(singular).is_some() && !is_array(singular))
+            .then(|| SuggestionComment::WrongPlurality(singular.to_owned()))
+    }
+
+    /// Check a name against the enabled naming rules
+    fn lint(&self, name: &str) -> Vec<SuggestionComment> {
+        let mut comments = vec![];
+        if Self::contains_uppercase(name) {
+            comments.push(SuggestionComment::WrongCase);
+        }
+        let mut invalid = name
+            .chars()
+            .filter(|c| {
+                !(c.is_ascii_lowercase()
+                    || c.is_ascii_uppercase()
+                    || c.is_ascii_digit()
+                    || *c == '_'
+                    || *c == '.')
+            })
+            .collect::<Vec<_>>();
+        if !invalid.is_empty() {
+            invalid.sort();
+            invalid.dedup();
+            comments.push(SuggestionComment::InvalidCharacter(invalid));
+        }
+        if name.split('.').any(str::is_empty) {
+            comments.push(SuggestionComment::EmptySegment);
+        }
+        if !self.has_namespace(name) {
+            comments.push(SuggestionComment::NoNamespace);
+        }
+        if name.starts_with("otel.") {
+            comments.push(SuggestionComment::ReservedPrefix("otel.".to_owned()));
+        }
+        // Undefined names in the prefixes are model namespaces or templates
+        if self.prefixes.contains(name) {
+            comments.push(SuggestionComment::NamespaceCollision);
+        }
+        if !self.naming.disabled.contains(&NamingRule::DeepNamespace) {
+            comments.extend(self.check_depth(name));
+        }
+        comments.extend(self.check_plurality(name));
+        let length = name.chars().count();
+        if length > self.naming.max_length {
+            comments.push(SuggestionComment::TooLong {
+                length,
+                max: self.naming.max_length,
+            });
+        }
+        comments.retain(|c| c.rule().is_none_or(|r| !self.naming.disabled.contains(&r)));
+        comments
+    }
+
+    /// Report a namespace deeper than the limits, or one encoding a code path,
+    /// with a flatter name. Reverse domain names are dropped, each class is
+    /// collapsed with its method into one snake cased segment and middle
+    /// segments are removed to fit within the limits, keeping the nearest
+    /// model namespace. The suggestion is always shallower than the name.
+    fn check_depth(&self, name: &str) -> Option<SuggestionComment> {
+        let limits = self.namespace_limits;
+        let segments = name.split('.').collect::<Vec<_>>();
+        let depth = segments.len() - 1;
+        let model_depth = self
+            .prefix_exists(name)
+            .map(|prefix| prefix.split('.').count());
+        let code_path = is_code_path(&segments);
+        let too_deep = depth > limits.max_depth
+            || model_depth.is_some_and(|d| depth > d + limits.max_extra_depth);
+        if !too_deep && !code_path {
+            return None;
+        }
+
+        let mut keep = model_depth.unwrap_or(1);
+        let mut flat = if is_reverse_domain(&segments) {
+            keep = 1;
+            collapse_code_path(&segments[2..])
+        } else {
+            collapse_code_path(&segments)
+        };
+        let max_depth = model_depth.map_or(limits.max_depth, |d| {
+            limits.max_depth.min(d + limits.max_extra_depth)
+        });
+        if flat.len() > max_depth + 1 {
+            let front = keep.min(max_depth);
+            let back = max_depth + 1 - front;
+            flat = [&flat[..front], &flat[flat.len() - back..]].concat();
+        }
+        let suggested = flat.join(".");
+        Some(SuggestionComment::DeepNamespace { depth, suggested })
+    }
+
     fn similar(&self, input: &str) -> Option<Vec<String>> {
         // See if there are some obvious similarities
 
@@ -289,34 +1241,18 @@ impl SemanticConventions {
         let mut similars: Vec<String> = self
             .attribute_map
             .iter()
+            .chain(self.templates.iter())
             .filter_map(|(key, value)| {
-                if let Some(attribute) = value {
-                    if attribute.deprecated.is_none() && (jaro(input, key) > 0.85) {
-                        Some(key.clone())
-                    } else {
-                        None
-                    }
+                // Builtins have no attribute and are never deprecated
+                let deprecated = value.as_ref().is_some_and(|a| a.deprecation().is_some());
+                if !deprecated && (jaro(input, key) > 0.85) {
+                    Some(key.clone())
                 } else {
                     None
                 }
             })
             .collect();
-        similars.extend(
-            self.templates
-                .iter()
-                .filter_map(|(key, value)| {
-                    if let Some(attribute) = value {
-                        if attribute.deprecated.is_none() && (jaro(input, key) > 0.85) {
-                            Some(key.clone())
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<String>>(),
-        );
+        similars.sort();
 
         if !similars.is_empty() {
             Some(similars)
@@ -337,42 +1273,184 @@ impl SemanticConventions {
         }
     }
 
+    /// The template attribute for a prefix or its closest ancestor, e.g.
+    /// `http.request.header` for `http.request.header.x-custom`.
+    pub fn find_template(&self, prefix: &str) -> Option<&str> {
+        let mut prefix = prefix;
+        loop {
+            if let Some((name, _)) = self.templates.get_key_value(prefix) {
+                return Some(name);
+            }
+            prefix = prefix.rsplit_once('.')?.0;
+        }
+    }
+
+    /// The model attribute for a name, either directly or via a template.
+    fn find_attribute(&self, name: &str) -> Option<&Attribute> {
+        match self.attribute_map.get(name) {
+            Some(attribute) => attribute.as_ref(),
+            None => self.matches_template(name),
+        }
+    }
+
+    /// Whether the name is an attribute, template or builtin of the model
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.attribute_map.contains_key(name) || self.matches_template(name).is_some()
+    }
+
+    /// The enum type of the attribute, if it is an enum.
+    pub fn get_enum(&self, name: &str) -> Option<&ComplexType> {
+        match self.attribute_map.get(name) {
+            Some(Some(Attribute {
+                r#type: Some(Type::Complex(complex_type)),
+                ..
+            })) => Some(complex_type),
+            _ => None,
+        }
+    }
+
+    /// The simple type of the matching attribute, e.g. `int`, without any
+    /// template wrapper, and its value constraints from the model. Enums
+    /// have no simple type.
+    pub fn get_value_type(&self, name: &str) -> Option<(&str, Option<&Constraints>)> {
+        let attribute = self.find_attribute(name)?;
+        let Some(Type::Simple(s)) = &attribute.r#type else {
+            return None;
+        };
+        let s = s
+            .strip_prefix("template[")
+            .and_then(|s| s.strip_suffix(']'))
+            .unwrap_or(s);
+        let constraints = attribute
+            .annotations
+            .as_ref()
+            .and_then(|a| a.honey_health.as_ref());
+        Some((s, constraints))
+    }
+
+    /// The stability of the matching attribute when it is explicitly marked
+    /// as anything other than stable, e.g. `experimental` or `development`.
+    /// Deprecated attributes are already reported as bad.
+    pub fn get_unstable(&self, name: &str) -> Option<String> {
+        let attribute = self.find_attribute(name)?;
+        if attribute.deprecation().is_some() {
+            return None;
+        }
+        match attribute.stability.as_deref() {
+            None | Some("stable") | Some("deprecated") => None,
+            Some(stability) => Some(stability.to_owned()),
+        }
+    }
+
+    /// Check the Honeycomb column type can hold the attribute's model type.
+    fn check_type(&self, name: &str, column_type: &str) -> Option<SuggestionComment> {
+        let attribute = self.find_attribute(name)?;
+        let expected = attribute.r#type.as_ref()?.column_types()?;
+        if expected.contains(&column_type) {
+            None
+        } else {
+            Some(SuggestionComment::WrongType {
+                expected: expected[0].to_owned(),
+                found: column_type.to_owned(),
+            })
+        }
+    }
+
+    /// The scopes the model expects the attribute to be set in, from the
+    /// types of the groups using it. `None` if only registry or other group
+    /// types use it.
+    pub fn expected_scopes(&self, name: &str) -> Option<BTreeSet<AttributeScope>> {
+        let key = if self.attribute_map.contains_key(name) {
+            name
+        } else {
+            name.rsplit_once('.')
+                .map(|(template, _)| template)
+                .filter(|template| self.templates.contains_key(*template))?
+        };
+        let scopes = self
+            .groups
+            .values()
+            .filter(|group| group.attributes.contains_key(key))
+            .filter_map(|group| AttributeScope::from_group_type(group.r#type.as_deref()?))
+            .collect::<BTreeSet<_>>();
+        if scopes.is_empty() {
+            None
+        } else {
+            Some(scopes)
+        }
+    }
+
+    /// Check the scopes a column was found in against the model.
+    fn check_scopes(&self, name: &str, scopes: &[AttributeScope]) -> Vec<SuggestionComment> {
+        let Some(expected) = self.expected_scopes(name) else {
+            return vec![];
+        };
+        scopes
+            .iter()
+            .filter(|scope| **scope != AttributeScope::Link && !expected.contains(scope))
+            .map(|scope| SuggestionComment::WrongScope {
+                expected: expected.iter().copied().collect(),
+                found: *scope,
+            })
+            .collect()
+    }
+
+    /// Add the checks of a column in one dataset, its Honeycomb type and the
+    /// scopes it was found in if known, to the suggestion for its name. A
+    /// column matching the model with the wrong type or in the wrong scope is
+    /// bad.
+    pub fn check_column(
+        &self,
+        suggestion: Suggestion,
+        name: &str,
+        column_type: &str,
+        scopes: &[AttributeScope],
+    ) -> Suggestion {
+        let mut wrong = self.check_scopes(name, scopes);
+        if let Some(wrong_type) = self.check_type(name, column_type) {
+            wrong.insert(0, wrong_type);
+        }
+        if wrong.is_empty() {
+            return suggestion;
+        }
+        match suggestion {
+            Suggestion::Matching => Suggestion::Bad(wrong),
+            Suggestion::Missing(mut comments) | Suggestion::Bad(mut comments) => {
+                comments.extend(wrong);
+                Suggestion::Bad(comments)
+            }
+        }
+    }
+
     /// Given the input attribute name, make an improvement suggestion.
     pub fn get_suggestion(&self, name: &str) -> Suggestion {
         // Is this already a semantic convention
         if let Some(attribute) = self.attribute_map.get(name) {
             // Is it deprecated?
             if let Some(attribute) = &attribute {
-                if let Some(deprecated) = &attribute.deprecated {
-                    return Suggestion::Bad(vec![SuggestionComment::Deprecated(
-                        deprecated.clone(),
-                    )]);
+                if let Some(deprecated) = attribute.deprecation() {
+                    return Suggestion::Bad(vec![SuggestionComment::Deprecated(deprecated)]);
                 } else {
                     return Suggestion::Matching;
                 }
             }
             Suggestion::Matching
         } else if let Some(attribute) = self.matches_template(name) {
-            if let Some(deprecated) = &attribute.deprecated {
-                return Suggestion::Bad(vec![SuggestionComment::Deprecated(deprecated.clone())]);
+            if let Some(deprecated) = attribute.deprecation() {
+                return Suggestion::Bad(vec![SuggestionComment::Deprecated(deprecated)]);
             } else {
                 return Suggestion::Matching;
             }
         } else {
-            let mut bad = false;
             // get all the suggestion comments
-            let mut comments = Vec::new();
-            if Self::contains_uppercase(name) {
-                comments.push(SuggestionComment::WrongCase);
-                bad = true;
-            }
+            let mut comments = self.lint(name);
+            let bad = comments.iter().any(|c| {
+                c.rule()
+                    .is_some_and(|r| self.naming.severity(r) == Severity::Error)
+            });
             if let Some(s) = self.prefix_exists(name) {
                 comments.push(SuggestionComment::Extends(s));
             }
-            if !self.has_namespace(name) {
-                comments.push(SuggestionComment::NoNamespace);
-                bad = true;
-            }
             if let Some(similar_names) = self.similar(name) {
                 comments.push(SuggestionComment::Similar(similar_names));
             }
@@ -391,11 +1469,7 @@ mod tests {
 
     #[test]
     fn test_populate_builtins() {
-        let mut sc = SemanticConventions {
-            attribute_map: HashMap::new(),
-            prefixes: HashSet::new(),
-            templates: HashMap::new(),
-        };
+        let mut sc = SemanticConventions::default();
         sc.populate_builtins();
         assert!(sc.attribute_map.contains_key("duration_ms"));
         assert!(sc.prefixes.contains("meta"));
@@ -409,11 +1483,7 @@ mod tests {
 
     #[test]
     fn test_insert_prefixes() {
-        let mut sc = SemanticConventions {
-            attribute_map: HashMap::new(),
-            prefixes: HashSet::new(),
-            templates: HashMap::new(),
-        };
+        let mut sc = SemanticConventions::default();
         sc.insert_prefixes("a.b.c");
         assert!(sc.prefixes.contains("a"));
         assert!(sc.prefixes.contains("a.b"));
@@ -422,26 +1492,817 @@ mod tests {
 
     #[test]
     fn test_prefix_exists() {
-        let mut sc = SemanticConventions {
-            attribute_map: HashMap::new(),
-            prefixes: HashSet::new(),
-            templates: HashMap::new(),
-        };
+        let mut sc = SemanticConventions::default();
         sc.insert_prefixes("a.b.c");
         assert_eq!(sc.prefix_exists("a.b.c.d"), Some("a.b.c".to_string()));
         assert_eq!(sc.prefix_exists("a.d"), Some("a".to_string()));
         assert_eq!(sc.prefix_exists("x.y.z"), None);
     }
 
+    fn column_suggestion(
+        sc: &SemanticConventions,
+        name: &str,
+        column_type: &str,
+        scopes: &[AttributeScope],
+    ) -> Suggestion {
+        sc.check_column(sc.get_suggestion(name), name, column_type, scopes)
+    }
+
     #[test]
-    fn test_similar() {
-        let mut sc = SemanticConventions {
-            attribute_map: HashMap::new(),
-            prefixes: HashSet::new(),
-            templates: HashMap::new(),
+    fn test_merge_suggestions() {
+        let wrong_type = SuggestionComment::WrongType {
+            expected: "integer".to_string(),
+            found: "string".to_string(),
         };
+        assert_eq!(
+            Suggestion::merge(&[Suggestion::Matching, Suggestion::Matching]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            Suggestion::merge(&[
+                Suggestion::Matching,
+                Suggestion::Bad(vec![wrong_type.clone()])
+            ]),
+            Suggestion::Bad(vec![wrong_type.clone()])
+        );
+        assert_eq!(
+            Suggestion::merge(&[
+                Suggestion::Missing(vec![SuggestionComment::WrongCase]),
+                Suggestion::Missing(vec![
+                    SuggestionComment::WrongCase,
+                    SuggestionComment::NoNamespace
+                ]),
+            ]),
+            Suggestion::Missing(vec![
+                SuggestionComment::WrongCase,
+                SuggestionComment::NoNamespace
+            ])
+        );
+    }
+
+    #[test]
+    fn test_similar() {
+        let mut sc = SemanticConventions::default();
         sc.attribute_map.insert("test".to_string(), None);
         assert_eq!(sc.similar("test"), Some(vec!["test".to_string()]));
         assert_eq!(sc.similar("x"), None);
     }
+
+    fn load(sc: &mut SemanticConventions, files: &[(&str, &str)]) -> anyhow::Result<()> {
+        let mut loaded = vec![];
+        for (path, yaml) in files {
+            let groups: Groups = serde_yaml::from_str(yaml)?;
+            loaded.extend(sc.register_groups(PathBuf::from(path), groups));
+        }
+        sc.resolve_groups(&loaded)
+    }
+
+    #[test]
+    fn test_resolve_ref_and_extends() {
+        let mut sc = SemanticConventions::default();
+        let registry = r#"
+groups:
+  - id: registry.http
+    type: attribute_group
+    attributes:
+      - id: http.route
+        type: string
+        brief: The matched route
+        requirement_level: recommended
+      - id: http.request.method
+        type: string
+"#;
+        let spans = r#"
+groups:
+  - id: attributes.http.common
+    type: attribute_group
+    attributes:
+      - ref: http.request.method
+        requirement_level: required
+  - id: span.http.server
+    type: span
+    span_kind: server
+    extends: attributes.http.common
+    attributes:
+      - ref: http.route
+        requirement_level:
+          conditionally_required: If available
+        brief: Overridden
+"#;
+        // Files are resolved together regardless of load order
+        load(
+            &mut sc,
+            &[("spans.yaml", spans), ("registry.yaml", registry)],
+        )
+        .unwrap();
+        let group = &sc.groups["span.http.server"];
+        assert_eq!(group.span_kind.as_deref(), Some("server"));
+        assert_eq!(group.attributes.len(), 2);
+        let route = &group.attributes["http.route"];
+        assert_eq!(route.brief.as_deref(), Some("Overridden"));
+        assert!(matches!(
+            &route.requirement_level,
+            Some(RequirementLevel::Detailed(m)) if m.contains_key("conditionally_required")
+        ));
+        let method = &group.attributes["http.request.method"];
+        assert_eq!(
+            method.requirement_level,
+            Some(RequirementLevel::Simple("required".to_string()))
+        );
+        assert_eq!(
+            group.requirement_levels(),
+            vec![
+                ("http.request.method", "required"),
+                ("http.route", "conditionally_required")
+            ]
+        );
+        // The flat map keeps the registry definition
+        let route = sc.attribute_map["http.route"].as_ref().unwrap();
+        assert_eq!(route.brief.as_deref(), Some("The matched route"));
+    }
+
+    #[test]
+    fn test_resolve_unresolved_ref() {
+        let mut sc = SemanticConventions::default();
+        let spans = r#"
+groups:
+  - id: span.db
+    type: span
+    extends: attributes.missing
+    attributes:
+      - ref: db.system
+"#;
+        let err = load(&mut sc, &[("spans.yaml", spans)])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("spans.yaml: group `span.db` has unresolved ref `db.system`"));
+        assert!(err.contains("spans.yaml: group `span.db` extends unknown group"));
+    }
+
+    #[test]
+    fn test_resolve_duplicate_group() {
+        let mut sc = SemanticConventions::default();
+        let group = r#"
+groups:
+  - id: registry.http
+    type: attribute_group
+    attributes:
+      - id: http.route
+        type: string
+"#;
+        let err = load(&mut sc, &[("a.yaml", group), ("b.yaml", group)])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("b.yaml: group `registry.http` is already defined in a.yaml"));
+    }
+
+    #[test]
+    fn test_resolve_legacy_prefix() {
+        let mut sc = SemanticConventions::default();
+        let legacy = r#"
+groups:
+  - id: rpc
+    prefix: rpc
+    type: span
+    attributes:
+      - id: system
+        type: string
+      - ref: rpc.system
+"#;
+        load(&mut sc, &[("rpc.yaml", legacy)]).unwrap();
+        assert!(sc.attribute_map.contains_key("rpc.system"));
+        assert!(sc.groups["rpc"].attributes.contains_key("rpc.system"));
+    }
+
+    #[test]
+    fn test_deprecated_schemas() {
+        let mut sc = SemanticConventions::default();
+        let legacy = r#"
+groups:
+  - id: http
+    prefix: http
+    type: attribute_group
+    attributes:
+      - id: scheme
+        type: string
+        deprecated: Replaced by `url.scheme` instead.
+      - id: flavor
+        type: string
+        stability: deprecated
+"#;
+        let current = r#"
+groups:
+  - id: registry.http.deprecated
+    type: attribute_group
+    attributes:
+      - id: http.method
+        type: string
+        stability: development
+        deprecated:
+          reason: renamed
+          renamed_to: http.request.method
+      - id: http.user_agent
+        type: string
+        deprecated:
+          reason: obsoleted
+          note: No longer captured.
+"#;
+        load(
+            &mut sc,
+            &[("legacy.yaml", legacy), ("current.yaml", current)],
+        )
+        .unwrap();
+
+        let suggestion = sc.get_suggestion("http.scheme");
+        assert_eq!(
+            suggestion.get_comments_string(false),
+            "Deprecated: Replaced by `url.scheme` instead."
+        );
+        let suggestion = sc.get_suggestion("http.method");
+        assert_eq!(suggestion.get_name(), "Bad");
+        assert_eq!(
+            suggestion.get_comments_string(false),
+            "Deprecated: Renamed to http.request.method"
+        );
+        assert_eq!(
+            suggestion.get_comments_string(true),
+            "Deprecated: Renamed to `http.request.method`"
+        );
+        assert_eq!(
+            sc.get_suggestion("http.user_agent")
+                .get_comments_string(false),
+            "Deprecated: No longer captured."
+        );
+        assert_eq!(sc.get_suggestion("http.flavor").get_name(), "Bad");
+    }
+
+    #[test]
+    fn test_resolved_registry() {
+        let catalog = r#"{
+  "catalog": {
+    "attributes": [
+      {"name": "http.request.method", "type": {"members": [{"id": "get", "value": "GET"}]}},
+      {"name": "http.method", "type": "string", "deprecated": {"reason": "renamed", "renamed_to": "http.request.method"}},
+      {"name": "http.request.header", "type": "template[string[]]"}
+    ]
+  },
+  "registry": {
+    "groups": [
+      {"id": "registry.http", "type": "attribute_group", "attributes": [0, 1, 2]},
+      {"id": "span.http.client", "type": "span", "span_kind": "client", "attributes": [0]}
+    ]
+  }
+}"#;
+        let materialized = r#"
+groups:
+  - id: registry.url
+    type: attribute_group
+    prefix: ""
+    attributes:
+      - name: url.scheme
+        type: string
+"#;
+        let mut sc = SemanticConventions::default();
+        let mut loaded = vec![];
+        for yaml in [catalog, materialized] {
+            let resolved: ResolvedSchema = serde_yaml::from_str(yaml).unwrap();
+            loaded.extend(sc.register_groups(
+                PathBuf::from("resolved.json"),
+                resolved.into_groups().unwrap(),
+            ));
+        }
+        sc.resolve_groups(&loaded).unwrap();
+        assert_eq!(
+            sc.get_suggestion("http.request.method"),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            sc.get_suggestion("http.request.header.accept"),
+            Suggestion::Matching
+        );
+        assert_eq!(sc.get_suggestion("url.scheme"), Suggestion::Matching);
+        assert_eq!(
+            sc.get_suggestion("http.method").get_comments_string(false),
+            "Deprecated: Renamed to http.request.method"
+        );
+        assert!(sc.groups["span.http.client"]
+            .attributes
+            .contains_key("http.request.method"));
+
+        let missing: ResolvedSchema =
+            serde_yaml::from_str(r#"{"registry": {"groups": [{"id": "g", "attributes": [3]}]}}"#)
+                .unwrap();
+        assert!(missing.into_groups().is_err());
+    }
+
+    #[test]
+    fn test_column_types() {
+        let mut sc = SemanticConventions::default();
+        sc.populate_builtins();
+        let registry = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: http.response.status_code
+        type: int
+      - id: server.port
+        type: int
+      - id: http.client.duration
+        type: double
+      - id: http.request.header
+        type: template[string[]]
+      - id: rpc.grpc.status_code
+        type:
+          members:
+            - id: ok
+              value: 0
+      - id: os.type
+        type:
+          members:
+            - id: linux
+              value: linux
+      - id: http.scheme
+        type: string
+        deprecated: Replaced by `url.scheme`.
+"#;
+        load(&mut sc, &[("registry.yaml", registry)]).unwrap();
+        assert_eq!(
+            column_suggestion(&sc, "http.response.status_code", "string", &[]),
+            Suggestion::Bad(vec![SuggestionComment::WrongType {
+                expected: "integer".to_string(),
+                found: "string".to_string()
+            }])
+        );
+        assert_eq!(
+            column_suggestion(&sc, "server.port", "float", &[]).get_comments_string(false),
+            "WrongType expected integer found float"
+        );
+        assert_eq!(
+            column_suggestion(&sc, "server.port", "integer", &[]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(&sc, "http.client.duration", "integer", &[]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(&sc, "http.request.header.accept", "string", &[]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(&sc, "rpc.grpc.status_code", "string", &[]).get_name(),
+            "Bad"
+        );
+        assert_eq!(
+            column_suggestion(&sc, "os.type", "string", &[]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(&sc, "http.scheme", "integer", &[]).get_comments_string(true),
+            "Deprecated: Replaced by `url.scheme`.; WrongType expected `string` found `integer`"
+        );
+        assert_eq!(
+            column_suggestion(&sc, "duration_ms", "float", &[]),
+            Suggestion::Matching
+        );
+    }
+
+    #[test]
+    fn test_get_value_type() {
+        let mut sc = SemanticConventions::default();
+        let registry = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: app.retry.count
+        type: int
+        annotations:
+          honey_health:
+            min: 0
+            max: 10
+      - id: app.header
+        type: template[string]
+      - id: app.kind
+        type:
+          members:
+            - id: a
+              value: a
+"#;
+        load(&mut sc, &[("registry.yaml", registry)]).unwrap();
+        let (value_type, constraints) = sc.get_value_type("app.retry.count").unwrap();
+        assert_eq!(value_type, "int");
+        let constraints = constraints.unwrap();
+        assert_eq!((constraints.min, constraints.max), (Some(0.0), Some(10.0)));
+        assert!(constraints.pattern.is_none());
+        let (value_type, constraints) = sc.get_value_type("app.header.x").unwrap();
+        assert_eq!(value_type, "string");
+        assert!(constraints.is_none());
+        assert!(sc.get_value_type("app.kind").is_none());
+    }
+
+    #[test]
+    fn test_find_template() {
+        let mut sc = SemanticConventions::default();
+        let registry = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: http.request.header
+        type: template[string[]]
+"#;
+        load(&mut sc, &[("registry.yaml", registry)]).unwrap();
+        assert_eq!(
+            sc.find_template("http.request.header"),
+            Some("http.request.header")
+        );
+        assert_eq!(
+            sc.find_template("http.request.header.x"),
+            Some("http.request.header")
+        );
+        assert_eq!(sc.find_template("http.request"), None);
+        assert_eq!(sc.find_template(""), None);
+    }
+
+    #[test]
+    fn test_record_root() {
+        let mut sc = SemanticConventions::default();
+        sc.populate_builtins();
+        let first = r#"
+groups:
+  - id: registry.app
+    type: attribute_group
+    attributes:
+      - id: app.name
+        type: string
+"#;
+        let second = r#"
+groups:
+  - id: registry.app.extra
+    type: attribute_group
+    attributes:
+      - id: app.header
+        type: template[string]
+"#;
+        load(&mut sc, &[("first.yaml", first)]).unwrap();
+        sc.record_root("/models/first");
+        load(&mut sc, &[("second.yaml", second)]).unwrap();
+        sc.record_root("/models/second");
+        assert_eq!(sc.attribute_roots["app.name"], "/models/first");
+        assert_eq!(sc.attribute_roots["app.header"], "/models/second");
+        assert!(!sc.attribute_roots.contains_key("span.kind"));
+    }
+
+    #[test]
+    fn test_get_unstable() {
+        let mut sc = SemanticConventions::default();
+        sc.populate_builtins();
+        let registry = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: http.route
+        type: string
+        stability: stable
+      - id: gen_ai.system
+        type: string
+        stability: development
+      - id: k8s.pod.label
+        type: template[string]
+        stability: experimental
+      - id: custom.thing
+        type: string
+      - id: http.flavor
+        type: string
+        stability: deprecated
+      - id: gen_ai.prompt
+        type: string
+        stability: development
+        deprecated:
+          reason: removed
+"#;
+        load(&mut sc, &[("registry.yaml", registry)]).unwrap();
+        assert_eq!(sc.get_unstable("http.route"), None);
+        assert_eq!(
+            sc.get_unstable("gen_ai.system"),
+            Some("development".to_string())
+        );
+        assert_eq!(
+            sc.get_unstable("k8s.pod.label.app"),
+            Some("experimental".to_string())
+        );
+        assert_eq!(sc.get_unstable("custom.thing"), None);
+        assert_eq!(sc.get_unstable("http.flavor"), None);
+        // Deprecated and still marked development
+        assert_eq!(sc.get_unstable("gen_ai.prompt"), None);
+        assert_eq!(sc.get_unstable("duration_ms"), None);
+        assert_eq!(sc.get_unstable("not.in.model"), None);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let mut sc = SemanticConventions::default();
+        let model = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: service.name
+        type: string
+      - id: http.request.method
+        type: string
+      - id: exception.message
+        type: string
+      - id: k8s.pod.label
+        type: template[string]
+      - id: custom.thing
+        type: string
+  - id: service
+    type: resource
+    attributes:
+      - ref: service.name
+  - id: k8s.pod
+    type: entity
+    attributes:
+      - ref: k8s.pod.label
+  - id: http.server
+    type: span
+    attributes:
+      - ref: http.request.method
+      - ref: exception.message
+  - id: exception
+    type: event
+    attributes:
+      - ref: exception.message
+"#;
+        load(&mut sc, &[("model.yaml", model)]).unwrap();
+        assert_eq!(
+            sc.expected_scopes("exception.message"),
+            Some(BTreeSet::from([
+                AttributeScope::Span,
+                AttributeScope::SpanEvent
+            ]))
+        );
+        assert_eq!(
+            sc.expected_scopes("k8s.pod.label.app"),
+            Some(BTreeSet::from([AttributeScope::Resource]))
+        );
+        assert_eq!(sc.expected_scopes("custom.thing"), None);
+        assert_eq!(sc.expected_scopes("not.in.model"), None);
+
+        assert_eq!(
+            column_suggestion(&sc, "service.name", "string", &[AttributeScope::Resource]),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(
+                &sc,
+                "service.name",
+                "string",
+                &[AttributeScope::Resource, AttributeScope::Span]
+            ),
+            Suggestion::Bad(vec![SuggestionComment::WrongScope {
+                expected: vec![AttributeScope::Resource],
+                found: AttributeScope::Span
+            }])
+        );
+        assert_eq!(
+            column_suggestion(
+                &sc,
+                "http.request.method",
+                "string",
+                &[AttributeScope::Resource]
+            )
+            .get_comments_string(false),
+            "WrongScope expected span found resource"
+        );
+        // Links are not modelled so are never wrong
+        assert_eq!(
+            column_suggestion(
+                &sc,
+                "http.request.method",
+                "string",
+                &[AttributeScope::Link]
+            ),
+            Suggestion::Matching
+        );
+        assert_eq!(
+            column_suggestion(&sc, "custom.thing", "string", &[AttributeScope::Span]),
+            Suggestion::Matching
+        );
+        // The same name is checked with the scopes of each dataset
+        let base = sc.get_suggestion("service.name");
+        assert_eq!(
+            sc.check_column(
+                base.clone(),
+                "service.name",
+                "string",
+                &[AttributeScope::Resource]
+            ),
+            Suggestion::Matching
+        );
+        assert_ne!(
+            sc.check_column(base, "service.name", "string", &[AttributeScope::Span]),
+            Suggestion::Matching
+        );
+    }
+
+    #[test]
+    fn test_closest_variant() {
+        let complex_type: ComplexType = serde_yaml::from_str(
+            r#"
+members:
+  - id: get
+    value: GET
+  - id: linux
+    value: Linux
+  - id: windows
+    value: windows
+  - id: mac
+    value: Mac
+    stability: deprecated
+"#,
+        )
+        .unwrap();
+        let closest = |v| complex_type.closest_variant(v).map(|m| m.id.as_str());
+        assert_eq!(closest("GET "), Some("get"));
+        assert_eq!(closest("linux"), Some("linux"));
+        assert_eq!(closest("windwos"), Some("windows"));
+        assert_eq!(closest("Mac OS"), None);
+        assert_eq!(closest("mac"), None);
+        assert!(!complex_type.allow_custom_values);
+    }
+
+    #[test]
+    fn test_integer_enum_is_defined() {
+        let complex_type: ComplexType = serde_yaml::from_str(
+            r#"
+allow_custom_values: true
+members:
+  - id: ok
+    value: 0
+    brief: Not an error
+  - id: cancelled
+    value: 1
+"#,
+        )
+        .unwrap();
+        assert!(complex_type.allow_custom_values);
+        assert!(complex_type.is_defined("0"));
+        assert!(complex_type.is_defined("1.0"));
+        assert!(!complex_type.is_defined("2"));
+        assert!(!complex_type.is_defined("ok"));
+        assert!(complex_type.closest_variant("0 ").is_none());
+        assert_eq!(
+            complex_type.members[0].brief.as_deref(),
+            Some("Not an error")
+        );
+    }
+
+    #[test]
+    fn test_check_depth() {
+        fn deep(sc: &SemanticConventions, name: &str) -> Option<(usize, String)> {
+            match sc.check_depth(name) {
+                Some(SuggestionComment::DeepNamespace { depth, suggested }) => {
+                    Some((depth, suggested))
+                }
+                _ => None,
+            }
+        }
+        let mut sc = SemanticConventions::default();
+        sc.insert_prefixes("http.request.method");
+        assert_eq!(deep(&sc, "http.request.header.x.y"), None);
+        // Four levels beyond the model's `http` namespace
+        assert_eq!(
+            deep(&sc, "http.a.b.c.d.size"),
+            Some((5, "http.b.c.d.size".to_owned()))
+        );
+        assert_eq!(
+            deep(&sc, "com.acme.service.OrderController.create.duration"),
+            Some((5, "service.order_create.duration".to_owned()))
+        );
+        // Within the limits but a class and method, collapsed to be shallower
+        assert_eq!(
+            deep(&sc, "app.OrderController.create.duration"),
+            Some((3, "app.order_create.duration".to_owned()))
+        );
+        assert_eq!(
+            deep(&sc, "app.Checkout.submit.items.count"),
+            Some((4, "app.checkout_submit.items.count".to_owned()))
+        );
+        for name in [
+            "http.a.b.c.d.size",
+            "com.acme.service.OrderController.create.duration",
+            "app.OrderController.create.duration",
+            "com.acme.order.total",
+        ] {
+            let (depth, suggested) = deep(&sc, name).unwrap();
+            assert!(suggested.split('.').count() - 1 < depth, "{}", suggested);
+        }
+        assert_eq!(
+            deep(&sc, "app.a.b.c.d.e.f"),
+            Some((6, "app.b.c.d.e.f".to_owned()))
+        );
+        assert_eq!(deep(&sc, "app.checkout.total"), None);
+        assert_eq!(deep(&sc, "TaskId"), None);
+        // `net` is a namespace, not a reverse domain
+        sc.insert_prefixes("net.peer.ip");
+        assert_eq!(deep(&sc, "net.peer.ip.foo"), None);
+        assert_eq!(deep(&sc, "io.acme.order.total"), None);
+
+        sc.namespace_limits.max_depth = 2;
+        assert_eq!(
+            deep(&sc, "app.checkout.cart.total"),
+            Some((3, "app.cart.total".to_owned()))
+        );
+        assert_eq!(
+            sc.get_suggestion("app.checkout.cart.total")
+                .get_comments_string(false),
+            "DeepNamespace depth 3 suggest app.cart.total"
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut sc = SemanticConventions::default();
+        let registry = r#"
+groups:
+  - id: registry
+    type: attribute_group
+    attributes:
+      - id: process.command_args
+        type: string[]
+      - id: http.route
+        type: string
+      - id: app.cart.total
+        type: int
+"#;
+        load(&mut sc, &[("registry.yaml", registry)]).unwrap();
+        let lint = |sc: &SemanticConventions, name: &str| {
+            sc.lint(name)
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(lint(&sc, "app.checkout.total").is_empty());
+        assert_eq!(
+            lint(&sc, "app.order-id/v2"),
+            vec!["InvalidCharacter '-' '/'"]
+        );
+        assert_eq!(lint(&sc, "app.café"), vec!["InvalidCharacter 'é'"]);
+        assert_eq!(lint(&sc, "app..total"), vec!["EmptySegment"]);
+        assert_eq!(lint(&sc, "app.total."), vec!["EmptySegment"]);
+        assert_eq!(lint(&sc, "app.cart"), vec!["NamespaceCollision"]);
+        assert_eq!(lint(&sc, "otel.custom"), vec!["ReservedPrefix otel."]);
+        assert_eq!(
+            lint(&sc, "process.command_arg"),
+            vec!["WrongPlurality expected process.command_args"]
+        );
+        assert_eq!(
+            lint(&sc, "http.routes"),
+            vec!["WrongPlurality expected http.route"]
+        );
+        assert_eq!(
+            lint(&sc, "Task Id"),
+            vec!["WrongCase", "InvalidCharacter ' '", "NoNamespace"]
+        );
+
+        sc.naming.max_length = 10;
+        assert_eq!(
+            lint(&sc, "app.checkout.total"),
+            vec!["TooLong length 18 max 10"]
+        );
+        assert_eq!(
+            sc.get_suggestion("app.checkout.total").get_name(),
+            "Missing"
+        );
+        assert_eq!(sc.get_suggestion("app..total").get_name(), "Bad");
+
+        sc.naming
+            .severities
+            .insert(NamingRule::EmptySegment, Severity::Warning);
+        assert_eq!(sc.get_suggestion("app..total").get_name(), "Missing");
+        sc.naming
+            .severities
+            .insert(NamingRule::MaxLength, Severity::Error);
+        assert_eq!(sc.get_suggestion("app.checkout.total").get_name(), "Bad");
+
+        sc.naming.disabled = HashSet::from([NamingRule::MaxLength, NamingRule::WrongCase]);
+        assert!(lint(&sc, "app.checkout.total").is_empty());
+        assert_eq!(lint(&sc, "Task.id"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_naming_rule_ids() {
+        for rule in NamingRule::ALL {
+            assert_eq!(NamingRule::from_id(rule.id()), Some(rule));
+            // The rules file uses the same ids
+            assert_eq!(serde_yaml::from_str::<NamingRule>(rule.id()).unwrap(), rule);
+        }
+        assert_eq!(NamingRule::from_id("WrongCase"), None);
+    }
 }
@@ -0,0 +1,188 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    semconv::{AttributeScope, SemanticConventions, Suggestion},
+    source::ColumnSource,
+};
+
+/// A column as read from a dataset. This holds the parts of a Honeycomb
+/// column that the reports use so they can be saved and re-read offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Column {
+    pub key_name: String,
+    pub r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_written: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Where the attribute was set, if known
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<AttributeScope>,
+}
+
+impl From<honeycomb_client::honeycomb::Column> for Column {
+    fn from(column: honeycomb_client::honeycomb::Column) -> Self {
+        Self {
+            key_name: column.key_name,
+            r#type: column.r#type,
+            last_written: Some(column.last_written.to_string()),
+            description: Some(column.description).filter(|d| !d.is_empty()),
+            scopes: vec![],
+        }
+    }
+}
+
+/// Variant to the number of events using it
+pub type VariantCounts = BTreeMap<String, u64>;
+
+/// Enum column name to the variants found by a group-by query
+pub type EnumVariants = BTreeMap<String, VariantCounts>;
+
+/// The variants of an enum column in a snapshot. Snapshots written before
+/// variant counts were recorded have a list of variants.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedVariants {
+    Counts(VariantCounts),
+    List(Vec<String>),
+}
+
+/// Read the enum variants of either snapshot format. Variants from a list
+/// have a count of 0.
+fn deserialize_enums<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<EnumVariants>, D::Error> {
+    let enums: Option<BTreeMap<String, SavedVariants>> = Option::deserialize(deserializer)?;
+    Ok(enums.map(|enums| {
+        enums
+            .into_iter()
+            .map(|(column, variants)| {
+                let counts = match variants {
+                    SavedVariants::Counts(counts) => counts,
+                    SavedVariants::List(list) => list.into_iter().map(|v| (v, 0)).collect(),
+                };
+                (column, counts)
+            })
+            .collect()
+    }))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset {
+    pub slug: String,
+    pub columns: Vec<Column>,
+    /// Only present if the snapshot was taken with `--enums`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_enums"
+    )]
+    pub enums: Option<EnumVariants>,
+}
+
+/// The datasets and columns saved with the `snapshot` command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub datasets: Vec<Dataset>,
+}
+
+impl Snapshot {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to read snapshot {}", path))
+    }
+
+    pub fn write(&self, path: &str) -> anyhow::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Find the variants of every column matching an enum attribute in the
+    /// model and store them with each dataset.
+    pub async fn add_enums(
+        &mut self,
+        semconv: &SemanticConventions,
+        source: &dyn ColumnSource,
+    ) -> anyhow::Result<()> {
+        for dataset in &mut self.datasets {
+            let column_ids = dataset
+                .columns
+                .iter()
+                .filter(|c| {
+                    semconv.get_suggestion(&c.key_name) == Suggestion::Matching
+                        && semconv.get_enum(&c.key_name).is_some()
+                })
+                .map(|c| c.key_name.clone())
+                .collect::<Vec<_>>();
+            let mut enums = BTreeMap::new();
+            if !column_ids.is_empty() {
+                enums.extend(source.group_by_variants(&dataset.slug, &column_ids).await?);
+            }
+            dataset.enums = Some(enums);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_snapshot() {
+        let json = r#"{
+  "datasets": [
+    {
+      "slug": "dataset1",
+      "columns": [
+        {"key_name": "http.route", "type": "string", "last_written": "2024-01-01T00:00:00Z"},
+        {"key_name": "server.port", "type": "integer"}
+      ],
+      "enums": {"http.request.method": {"GET": 120, "POST": 3}}
+    },
+    {"slug": "dataset2", "columns": []}
+  ]
+}"#;
+        let snapshot: Snapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(snapshot.datasets.len(), 2);
+        assert_eq!(snapshot.datasets[0].columns[1].r#type, "integer");
+        assert_eq!(snapshot.datasets[0].columns[1].description, None);
+        assert_eq!(
+            snapshot.datasets[0].enums.as_ref().unwrap()["http.request.method"],
+            VariantCounts::from([("GET".to_string(), 120), ("POST".to_string(), 3)])
+        );
+        assert!(snapshot.datasets[1].enums.is_none());
+
+        // Round trip without the optional fields
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("description"));
+        assert!(!json.contains("\"enums\":null"));
+    }
+
+    #[test]
+    fn test_read_snapshot_without_counts() {
+        let json = r#"{
+  "datasets": [
+    {
+      "slug": "dataset1",
+      "columns": [{"key_name": "http.request.method", "type": "string"}],
+      "enums": {"http.request.method": ["GET", "POST"]}
+    }
+  ]
+}"#;
+        let snapshot: Snapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            snapshot.datasets[0].enums.as_ref().unwrap()["http.request.method"],
+            VariantCounts::from([("GET".to_string(), 0), ("POST".to_string(), 0)])
+        );
+    }
+}
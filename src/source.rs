@@ -0,0 +1,395 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Debug,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use indicatif::ProgressBar;
+
+use crate::{
+    query::{self, Calculation, Filter, Order, QueryClient, QuerySpec},
+    semconv::{AttributeScope, SemanticConventions},
+    snapshot::{Column, Dataset, Snapshot, VariantCounts},
+};
+
+/// Where the datasets and their columns come from
+#[async_trait]
+pub trait ColumnSource: Debug + Send + Sync {
+    /// Read every dataset and its columns
+    async fn datasets(&self) -> anyhow::Result<Vec<Dataset>>;
+
+    /// Find the variants, and how often each is used, for each of the columns
+    /// in a dataset
+    async fn group_by_variants(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, VariantCounts)>>;
+
+    /// Count the distinct values of each of the columns in a dataset. Sources
+    /// without queries count the variants they hold.
+    async fn distinct_counts(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, u64)>> {
+        Ok(self
+            .group_by_variants(dataset_slug, column_ids)
+            .await?
+            .into_iter()
+            .map(|(column_id, variants)| (column_id, variants.len() as u64))
+            .collect())
+    }
+
+    /// Infer the scopes each of the columns in a dataset was set in. Sources
+    /// which read the scopes with the columns return nothing.
+    async fn scopes(
+        &self,
+        _dataset_slug: &str,
+        _column_ids: &[String],
+    ) -> anyhow::Result<HashMap<String, Vec<AttributeScope>>> {
+        Ok(HashMap::new())
+    }
+
+    /// Count the spans of a kind with the anchor attribute set, and how many
+    /// of those also have each of the attributes set.
+    async fn attribute_presence(
+        &self,
+        _dataset_slug: &str,
+        _span_kind: &str,
+        _anchor: &str,
+        _attributes: &[String],
+    ) -> anyhow::Result<(u64, Vec<u64>)> {
+        anyhow::bail!("The requirements report needs queries so only works with the Honeycomb API")
+    }
+}
+
+/// Fill in the scopes of columns matching attributes the model places in a
+/// resource, span or span event.
+///
+/// Honeycomb copies resource attributes onto every span, span event and
+/// link so columns the model expects on a resource are not inferred.
+pub async fn add_scopes(
+    semconv: &SemanticConventions,
+    source: &dyn ColumnSource,
+    datasets: &mut [Dataset],
+) -> anyhow::Result<()> {
+    for dataset in datasets {
+        let column_ids = dataset
+            .columns
+            .iter()
+            .filter(|c| {
+                c.scopes.is_empty()
+                    && semconv
+                        .expected_scopes(&c.key_name)
+                        .is_some_and(|s| !s.contains(&AttributeScope::Resource))
+            })
+            .map(|c| c.key_name.clone())
+            .collect::<Vec<_>>();
+        if column_ids.is_empty() {
+            continue;
+        }
+        let mut scopes = source.scopes(&dataset.slug, &column_ids).await?;
+        for column in &mut dataset.columns {
+            if let Some(s) = scopes.remove(&column.key_name) {
+                column.scopes = s;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A progress bar for the datasets or columns being read
+fn progress_bar(len: u64) -> ProgressBar {
+    ProgressBar::new(len).with_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap(),
+    )
+}
+
+/// The most days of data a query covers, however large `--last-written-days`
+const MAX_QUERY_DAYS: usize = 7;
+
+/// Read the columns of the datasets in the Honeycomb environment for the
+/// `HONEYCOMB_API_KEY`
+#[derive(Debug)]
+pub struct HoneycombSource {
+    include_datasets: Option<HashSet<String>>,
+    max_last_written_days: usize,
+    /// Only present when a report needs queries
+    query_client: Option<QueryClient>,
+}
+
+impl HoneycombSource {
+    /// Reports that query the events need `queries` to be true, so the API
+    /// key is checked for the permission before anything runs
+    pub async fn new(
+        include_datasets: Option<HashSet<String>>,
+        max_last_written_days: usize,
+        queries: bool,
+    ) -> anyhow::Result<Self> {
+        let query_client = if queries {
+            Some(QueryClient::new().await?)
+        } else {
+            None
+        };
+        Ok(Self {
+            include_datasets,
+            max_last_written_days,
+            query_client,
+        })
+    }
+
+    fn query_client(&self) -> anyhow::Result<&QueryClient> {
+        self.query_client
+            .as_ref()
+            .context("The Honeycomb source was created without queries")
+    }
+
+    /// The time range of every query, the last written days up to 7 days
+    fn query_range_seconds(&self) -> usize {
+        self.max_last_written_days.min(MAX_QUERY_DAYS) * 24 * 60 * 60
+    }
+}
+
+#[async_trait]
+impl ColumnSource for HoneycombSource {
+    async fn datasets(&self) -> anyhow::Result<Vec<Dataset>> {
+        let hc = honeycomb_client::get_honeycomb(&["columns", "createDatasets"])
+            .await?
+            .context("API key does not have required access")?;
+
+        let dataset_slugs = hc
+            .get_dataset_slugs(
+                self.max_last_written_days as i64,
+                self.include_datasets.clone(),
+            )
+            .await?;
+
+        let bar = progress_bar(dataset_slugs.len() as u64).with_message("Reading datasets...");
+        bar.inc(0);
+        let mut datasets = vec![];
+        hc.process_datasets_columns(
+            self.max_last_written_days as i64,
+            &dataset_slugs,
+            |_, columns| {
+                bar.inc(1);
+                datasets.push(Dataset {
+                    slug: dataset_slugs[datasets.len()].clone(),
+                    columns: columns.into_iter().map(Column::from).collect(),
+                    enums: None,
+                });
+            },
+        )
+        .await?;
+
+        bar.finish_and_clear();
+        Ok(datasets)
+    }
+
+    async fn group_by_variants(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, VariantCounts)>> {
+        let client = self.query_client()?;
+        let range_seconds = self.query_range_seconds();
+
+        let bar = progress_bar(column_ids.len() as u64)
+            .with_message(format!("Finding variants in {}...", dataset_slug));
+        bar.inc(0);
+        let mut results = vec![];
+        for column_id in column_ids {
+            let spec = QuerySpec {
+                breakdowns: vec![column_id.clone()],
+                calculations: vec![Calculation::count()],
+                filters: vec![Filter::exists(column_id)],
+                orders: vec![Order::count_descending()],
+                time_range: range_seconds,
+                limit: Some(1000),
+            };
+            let variants = client
+                .run(dataset_slug, &spec)
+                .await?
+                .iter()
+                .filter_map(|row| {
+                    let variant = query::as_string(row.get(column_id))?;
+                    Some((variant, query::as_count(row.get("COUNT"))))
+                })
+                .collect();
+            results.push((column_id.clone(), variants));
+            bar.inc(1);
+        }
+        bar.finish_and_clear();
+        Ok(results)
+    }
+
+    async fn distinct_counts(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, u64)>> {
+        let client = self.query_client()?;
+        let range_seconds = self.query_range_seconds();
+
+        let bar = progress_bar(column_ids.len() as u64)
+            .with_message(format!("Counting distinct values in {}...", dataset_slug));
+        bar.inc(0);
+        let mut results = vec![];
+        for column_id in column_ids {
+            let spec = QuerySpec {
+                calculations: vec![Calculation::count_distinct(column_id)],
+                time_range: range_seconds,
+                ..Default::default()
+            };
+            let rows = client.run(dataset_slug, &spec).await?;
+            let key = format!("COUNT_DISTINCT({})", column_id);
+            let count = query::as_count(rows.first().and_then(|r| r.get(&key)));
+            results.push((column_id.clone(), count));
+            bar.inc(1);
+        }
+        bar.finish_and_clear();
+        Ok(results)
+    }
+
+    async fn scopes(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<HashMap<String, Vec<AttributeScope>>> {
+        let client = self.query_client()?;
+        let range_seconds = self.query_range_seconds();
+
+        let bar = progress_bar(column_ids.len() as u64)
+            .with_message(format!("Inferring scopes in {}...", dataset_slug));
+        bar.inc(0);
+        let mut scopes = HashMap::new();
+        for column_id in column_ids {
+            // Span events and links are annotations on a span, spans have no
+            // annotation type
+            let spec = QuerySpec {
+                breakdowns: vec!["meta.annotation_type".to_owned()],
+                calculations: vec![Calculation::count()],
+                filters: vec![Filter::exists(column_id)],
+                time_range: range_seconds,
+                ..Default::default()
+            };
+            let rows = client.run(dataset_slug, &spec).await?;
+            let found = rows
+                .iter()
+                .filter(|row| query::as_count(row.get("COUNT")) > 0)
+                .filter_map(|row| match row.get("meta.annotation_type") {
+                    None | Some(serde_json::Value::Null) => Some(AttributeScope::Span),
+                    Some(v) => match v.as_str() {
+                        Some("span_event") => Some(AttributeScope::SpanEvent),
+                        Some("link") => Some(AttributeScope::Link),
+                        _ => None,
+                    },
+                })
+                .collect::<BTreeSet<_>>();
+            scopes.insert(column_id.clone(), found.into_iter().collect());
+            bar.inc(1);
+        }
+        bar.finish_and_clear();
+        Ok(scopes)
+    }
+
+    async fn attribute_presence(
+        &self,
+        dataset_slug: &str,
+        span_kind: &str,
+        anchor: &str,
+        attributes: &[String],
+    ) -> anyhow::Result<(u64, Vec<u64>)> {
+        let client = self.query_client()?;
+        let range_seconds = self.query_range_seconds();
+        let count = |attribute: Option<&String>| {
+            let mut filters = vec![
+                Filter::equals("span.kind", span_kind),
+                Filter::exists(anchor),
+            ];
+            filters.extend(attribute.map(|a| Filter::exists(a)));
+            let spec = QuerySpec {
+                calculations: vec![Calculation::count()],
+                filters,
+                time_range: range_seconds,
+                ..Default::default()
+            };
+            async move {
+                let rows = client.run(dataset_slug, &spec).await?;
+                anyhow::Ok(query::as_count(rows.first().and_then(|r| r.get("COUNT"))))
+            }
+        };
+        let total = count(None).await?;
+        let mut counts = vec![];
+        for attribute in attributes {
+            // No need to query again when nothing matched
+            counts.push(if total == 0 {
+                0
+            } else {
+                count(Some(attribute)).await?
+            });
+        }
+        Ok((total, counts))
+    }
+}
+
+/// Read the datasets from a file saved with the `snapshot` command
+#[derive(Debug)]
+pub struct SnapshotSource {
+    snapshot: Snapshot,
+}
+
+impl SnapshotSource {
+    pub fn new(path: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            snapshot: Snapshot::read(path)?,
+        })
+    }
+}
+
+#[async_trait]
+impl ColumnSource for SnapshotSource {
+    async fn datasets(&self) -> anyhow::Result<Vec<Dataset>> {
+        Ok(self.snapshot.datasets.clone())
+    }
+
+    async fn group_by_variants(
+        &self,
+        dataset_slug: &str,
+        column_ids: &[String],
+    ) -> anyhow::Result<Vec<(String, VariantCounts)>> {
+        let enums = self
+            .snapshot
+            .datasets
+            .iter()
+            .find(|d| d.slug == dataset_slug)
+            .and_then(|d| d.enums.as_ref())
+            .context("The snapshot has no enum variants, create it with --enums")?;
+        Ok(column_ids
+            .iter()
+            .filter_map(|c| enums.get(c).map(|variants| (c.clone(), variants.clone())))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(max_last_written_days: usize) -> HoneycombSource {
+        HoneycombSource {
+            include_datasets: None,
+            max_last_written_days,
+            query_client: None,
+        }
+    }
+
+    #[test]
+    fn test_query_range_seconds() {
+        assert_eq!(source(30).query_range_seconds(), 7 * 24 * 60 * 60);
+        assert_eq!(source(2).query_range_seconds(), 2 * 24 * 60 * 60);
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fs::File, io::BufReader};
+
+use anyhow::Context;
+use chrono::{NaiveDate, Utc};
+use glob::Pattern;
+use serde::Deserialize;
+
+/// Parse an ISO 8601 date e.g. `2025-06-30`
+fn parse_date(s: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .with_context(|| format!("Invalid date {}, expected YYYY-MM-DD", s))
+}
+
+#[derive(Debug, Deserialize)]
+struct SuppressionFile {
+    column: String,
+    datasets: Option<Vec<String>>,
+    reason: String,
+    expires: Option<String>,
+}
+
+/// An accepted Missing or Bad column, or a glob pattern of them
+#[derive(Debug)]
+pub struct Suppression {
+    pub column: String,
+    pattern: Pattern,
+    /// The datasets it applies to, all when `None`
+    pub datasets: Option<Vec<String>>,
+    pub reason: String,
+    /// The last day it applies
+    pub expires: Option<NaiveDate>,
+}
+
+impl Suppression {
+    fn matches(&self, dataset: &str, column: &str) -> bool {
+        self.pattern.matches(column)
+            && self
+                .datasets
+                .as_ref()
+                .is_none_or(|datasets| datasets.iter().any(|d| d == dataset))
+    }
+
+    fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+}
+
+/// Suppressions read from a YAML list, e.g.
+///
+/// ```yaml
+/// - column: vendor.legacy_*
+///   datasets: [billing]
+///   reason: Set by the vendor SDK
+///   expires: 2025-06-30
+/// ```
+#[derive(Debug)]
+pub struct Suppressions {
+    suppressions: Vec<Suppression>,
+    today: NaiveDate,
+}
+
+impl Default for Suppressions {
+    fn default() -> Self {
+        Suppressions {
+            suppressions: vec![],
+            today: Utc::now().date_naive(),
+        }
+    }
+}
+
+impl Suppressions {
+    pub fn read(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        let suppressions: Vec<SuppressionFile> = serde_yaml::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to read suppressions {}", path))?;
+        Self::new(suppressions, Utc::now().date_naive())
+            .with_context(|| format!("Invalid suppression in {}", path))
+    }
+
+    fn new(suppressions: Vec<SuppressionFile>, today: NaiveDate) -> anyhow::Result<Self> {
+        let suppressions = suppressions
+            .into_iter()
+            .map(|s| {
+                Ok(Suppression {
+                    pattern: Pattern::new(&s.column)
+                        .with_context(|| format!("Invalid column pattern {}", s.column))?,
+                    column: s.column,
+                    datasets: s.datasets,
+                    reason: s.reason,
+                    expires: s.expires.as_deref().map(parse_date).transpose()?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Suppressions {
+            suppressions,
+            today,
+        })
+    }
+
+    /// The first suppression that applies to a column of a dataset and has
+    /// not expired
+    pub fn find(&self, dataset: &str, column: &str) -> Option<&Suppression> {
+        self.suppressions
+            .iter()
+            .find(|s| !s.is_expired(self.today) && s.matches(dataset, column))
+    }
+
+    /// The suppressions that still apply
+    pub fn active(&self) -> Vec<&Suppression> {
+        self.suppressions
+            .iter()
+            .filter(|s| !s.is_expired(self.today))
+            .collect()
+    }
+
+    /// The suppressions past their expiry date, which no longer apply
+    pub fn expired(&self) -> Vec<&Suppression> {
+        self.suppressions
+            .iter()
+            .filter(|s| s.is_expired(self.today))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2024-02-29").unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2024-02-31").is_err());
+        assert!(parse_date("2024-04-31").is_err());
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("29/02/2024").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let suppressions: Vec<SuppressionFile> = serde_yaml::from_str(
+            r#"
+- column: vendor.legacy_*
+  datasets: [billing]
+  reason: Set by the vendor SDK
+- column: TaskId
+  reason: Renamed in the next release
+  expires: 2024-03-01
+- column: app.tmp
+  reason: Migration
+  expires: 2024-02-01
+"#,
+        )
+        .unwrap();
+        let suppressions =
+            Suppressions::new(suppressions, parse_date("2024-03-01").unwrap()).unwrap();
+        assert_eq!(
+            suppressions
+                .find("billing", "vendor.legacy_id")
+                .map(|s| s.reason.as_str()),
+            Some("Set by the vendor SDK")
+        );
+        assert!(suppressions.find("shop", "vendor.legacy_id").is_none());
+        // Applies on its expiry date
+        assert!(suppressions.find("shop", "TaskId").is_some());
+        assert!(suppressions.find("shop", "app.tmp").is_none());
+        assert_eq!(suppressions.active().len(), 2);
+        let expired = suppressions.expired();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].column, "app.tmp");
+    }
+}
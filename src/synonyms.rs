@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+/// Common abbreviations and the word they are normalised to
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("req", "request"),
+    ("resp", "response"),
+    ("res", "response"),
+    ("msg", "message"),
+    ("addr", "address"),
+    ("err", "error"),
+    ("svc", "service"),
+    ("env", "environment"),
+];
+
+/// Split a name into lowercase words on `.`, `_`, `-` and camelCase
+/// boundaries, expanding common abbreviations e.g. `reqId` is `request id`
+pub fn tokens(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    for part in name.split(['.', '_', '-']) {
+        let chars = part.chars().collect::<Vec<_>>();
+        let mut word = String::new();
+        for (i, c) in chars.iter().enumerate() {
+            // A new word starts at an upper case letter after a lower case
+            // one, or at the last upper case letter of an acronym e.g. the
+            // `S` in `HTTPStatus`
+            let boundary = c.is_uppercase()
+                && i > 0
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_ascii_digit()
+                    || (chars[i - 1].is_uppercase()
+                        && chars.get(i + 1).is_some_and(|n| n.is_lowercase())));
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.extend(c.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+        .into_iter()
+        .map(|w| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(abbreviation, _)| *abbreviation == w)
+                .map_or(w, |(_, word)| word.to_string())
+        })
+        .collect()
+}
+
+/// The key names with the same meaning share
+pub fn normalise(name: &str) -> String {
+    tokens(name).join(" ")
+}
+
+/// Group names that normalise to the same tokens. Only groups with more than
+/// one spelling are returned, in order of their normalised key.
+pub fn find_synonyms<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for name in names {
+        groups
+            .entry(normalise(name))
+            .or_default()
+            .push(name.to_owned());
+    }
+    groups
+        .into_values()
+        .map(|mut spellings| {
+            spellings.sort();
+            spellings.dedup();
+            spellings
+        })
+        .filter(|spellings| spellings.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(tokens("user_id"), vec!["user", "id"]);
+        assert_eq!(tokens("userId"), vec!["user", "id"]);
+        assert_eq!(tokens("user.id"), vec!["user", "id"]);
+        assert_eq!(tokens("HTTPStatus"), vec!["http", "status"]);
+        assert_eq!(tokens("http.req-size"), vec!["http", "request", "size"]);
+        assert_eq!(tokens("respCode2xx"), vec!["response", "code2xx"]);
+    }
+
+    #[test]
+    fn test_find_synonyms() {
+        let names = [
+            "user_id",
+            "userId",
+            "user.id",
+            "http.resp.status",
+            "http.response.status",
+            "http.route",
+        ];
+        assert_eq!(
+            find_synonyms(names),
+            vec![
+                vec!["http.resp.status", "http.response.status"],
+                vec!["user.id", "userId", "user_id"],
+            ]
+        );
+    }
+}
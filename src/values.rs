@@ -0,0 +1,225 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::semconv::Constraints;
+
+/// Something wrong with a value sampled from a column
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueViolation {
+    IntegerAsString,
+    NumberAsString,
+    BooleanAsString,
+    NotInteger,
+    NotNumber,
+    NotBoolean,
+    BelowMin(f64),
+    AboveMax(f64),
+    NoPatternMatch(String),
+    NoScheme,
+}
+
+impl Display for ValueViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueViolation::IntegerAsString => write!(f, "IntegerAsString"),
+            ValueViolation::NumberAsString => write!(f, "NumberAsString"),
+            ValueViolation::BooleanAsString => write!(f, "BooleanAsString"),
+            ValueViolation::NotInteger => write!(f, "NotInteger"),
+            ValueViolation::NotNumber => write!(f, "NotNumber"),
+            ValueViolation::NotBoolean => write!(f, "NotBoolean"),
+            ValueViolation::BelowMin(min) => write!(f, "BelowMin {}", min),
+            ValueViolation::AboveMax(max) => write!(f, "AboveMax {}", max),
+            ValueViolation::NoPatternMatch(pattern) => write!(f, "NoPatternMatch {}", pattern),
+            ValueViolation::NoScheme => write!(f, "NoScheme"),
+        }
+    }
+}
+
+/// The checks for the values of one column. Built in constraints for well
+/// known attributes, e.g. ports and HTTP status codes, are used unless the
+/// model gives its own.
+#[derive(Debug)]
+pub struct ValueRules {
+    value_type: String,
+    column_type: String,
+    min: Option<f64>,
+    max: Option<f64>,
+    pattern: Option<Regex>,
+    needs_scheme: bool,
+}
+
+impl ValueRules {
+    /// The rules for a column, `None` when there is nothing to check
+    pub fn new(
+        name: &str,
+        value_type: &str,
+        column_type: &str,
+        constraints: Option<&Constraints>,
+    ) -> anyhow::Result<Option<Self>> {
+        let (builtin_min, builtin_max) = match name {
+            "http.response.status_code" => (Some(100.0), Some(599.0)),
+            n if n.ends_with(".port") => (Some(0.0), Some(65535.0)),
+            _ => (None, None),
+        };
+        let constraints = constraints.cloned().unwrap_or_default();
+        let pattern = constraints
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .with_context(|| format!("Invalid pattern for {}", name))?;
+        let rules = ValueRules {
+            value_type: value_type.to_owned(),
+            column_type: column_type.to_owned(),
+            min: constraints.min.or(builtin_min),
+            max: constraints.max.or(builtin_max),
+            pattern,
+            needs_scheme: name == "url.full",
+        };
+        // Plain strings are only checked when constrained
+        let checked = matches!(value_type, "int" | "double" | "boolean")
+            || rules.min.is_some()
+            || rules.max.is_some()
+            || rules.pattern.is_some()
+            || rules.needs_scheme;
+        Ok(checked.then_some(rules))
+    }
+
+    /// Check a value, returning the first violation found
+    pub fn check(&self, value: &str) -> Option<ValueViolation> {
+        let is_string_column = self.column_type == "string";
+        let number = match self.value_type.as_str() {
+            "int" => match value.parse::<i64>() {
+                Ok(_) if is_string_column => return Some(ValueViolation::IntegerAsString),
+                Ok(i) => Some(i as f64),
+                // Integers in float columns come back as whole floats
+                Err(_) => match value.parse::<f64>() {
+                    Ok(f) if f.fract() == 0.0 && !is_string_column => Some(f),
+                    _ => return Some(ValueViolation::NotInteger),
+                },
+            },
+            "double" => match value.parse::<f64>() {
+                Ok(_) if is_string_column => return Some(ValueViolation::NumberAsString),
+                Ok(f) => Some(f),
+                Err(_) => return Some(ValueViolation::NotNumber),
+            },
+            "boolean" => match value {
+                "true" | "false" if is_string_column => {
+                    return Some(ValueViolation::BooleanAsString)
+                }
+                "true" | "false" => None,
+                _ => return Some(ValueViolation::NotBoolean),
+            },
+            _ => None,
+        };
+        if let Some(number) = number {
+            if let Some(min) = self.min.filter(|min| number < *min) {
+                return Some(ValueViolation::BelowMin(min));
+            }
+            if let Some(max) = self.max.filter(|max| number > *max) {
+                return Some(ValueViolation::AboveMax(max));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(value) {
+                return Some(ValueViolation::NoPatternMatch(pattern.to_string()));
+            }
+        }
+        if self.needs_scheme && !has_scheme(value) {
+            return Some(ValueViolation::NoScheme);
+        }
+        None
+    }
+}
+
+/// Whether a URL starts with a scheme e.g. `https:`
+fn has_scheme(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(name: &str, value_type: &str, column_type: &str) -> ValueRules {
+        ValueRules::new(name, value_type, column_type, None)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_type_violations() {
+        let port = rules("server.port", "int", "string");
+        assert_eq!(port.check("443"), Some(ValueViolation::IntegerAsString));
+        assert_eq!(port.check("https"), Some(ValueViolation::NotInteger));
+
+        let flag = rules("feature_flag.enabled", "boolean", "string");
+        assert_eq!(flag.check("true"), Some(ValueViolation::BooleanAsString));
+        assert_eq!(flag.check("yes"), Some(ValueViolation::NotBoolean));
+        let flag = rules("feature_flag.enabled", "boolean", "boolean");
+        assert_eq!(flag.check("false"), None);
+    }
+
+    #[test]
+    fn test_builtin_ranges() {
+        let port = rules("server.port", "int", "integer");
+        assert_eq!(port.check("443"), None);
+        assert_eq!(port.check("-1"), Some(ValueViolation::BelowMin(0.0)));
+        assert_eq!(port.check("70000"), Some(ValueViolation::AboveMax(65535.0)));
+
+        let status = rules("http.response.status_code", "int", "float");
+        assert_eq!(status.check("200"), None);
+        assert_eq!(status.check("200.5"), Some(ValueViolation::NotInteger));
+        assert_eq!(status.check("600"), Some(ValueViolation::AboveMax(599.0)));
+        assert_eq!(status.check("0"), Some(ValueViolation::BelowMin(100.0)));
+    }
+
+    #[test]
+    fn test_url_scheme() {
+        let url = rules("url.full", "string", "string");
+        assert_eq!(url.check("https://example.com/a?b=c"), None);
+        assert_eq!(url.check("mailto:someone@example.com"), None);
+        assert_eq!(url.check("example.com/a"), Some(ValueViolation::NoScheme));
+        assert_eq!(url.check("/a/b"), Some(ValueViolation::NoScheme));
+    }
+
+    #[test]
+    fn test_model_constraints() {
+        let constraints = Constraints {
+            min: Some(1.0),
+            max: None,
+            pattern: Some("^[a-z]+$".to_owned()),
+        };
+        let rules = ValueRules::new("app.name", "string", "string", Some(&constraints))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rules.check("shop"), None);
+        assert_eq!(
+            rules.check("Shop"),
+            Some(ValueViolation::NoPatternMatch("^[a-z]+$".to_owned()))
+        );
+
+        let port = ValueRules::new("server.port", "int", "integer", Some(&constraints))
+            .unwrap()
+            .unwrap();
+        assert_eq!(port.check("0"), Some(ValueViolation::BelowMin(1.0)));
+        // The model min replaces the builtin but the builtin max still applies
+        assert_eq!(port.check("70000"), Some(ValueViolation::AboveMax(65535.0)));
+
+        assert!(ValueRules::new("app.name", "string", "string", None)
+            .unwrap()
+            .is_none());
+        let invalid = Constraints {
+            pattern: Some("(".to_owned()),
+            ..Default::default()
+        };
+        assert!(ValueRules::new("app.name", "string", "string", Some(&invalid)).is_err());
+    }
+}